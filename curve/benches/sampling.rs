@@ -0,0 +1,68 @@
+//! `sample` (linear segment search) vs `PreparedCurve` (cached segments, binary search)
+//!
+//! cargo bench -p spline-curve
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use spline_curve::{sample, Curve, Interpolation, Point};
+
+const SAMPLES: usize = 256;
+
+/// evenly spaced points with pseudo random values (deterministic)
+fn curve(len: usize, interpolation: Interpolation) -> Curve {
+    let mut seed: u32 = 0x2545_f491;
+    let points = (0..len)
+        .map(|ind| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+
+            let x = ind as f32 / (len - 1) as f32;
+            Point::new(x, (seed % 1000) as f32 / 1000.0)
+        })
+        .collect();
+
+    Curve::new(points).with_interpolation(interpolation)
+}
+
+fn sampling(c: &mut Criterion) {
+    for interpolation in [Interpolation::CatmullRom, Interpolation::Bezier] {
+        let mut group = c.benchmark_group(format!("sample {}", interpolation));
+
+        for len in [10, 100, 10_000] {
+            let curve = curve(len, interpolation);
+            let prepared = curve.prepare();
+
+            group.bench_with_input(BenchmarkId::new("sample", len), &curve, |b, curve| {
+                b.iter(|| {
+                    for n in 0..SAMPLES {
+                        let t = n as f32 / (SAMPLES - 1) as f32;
+                        black_box(sample(curve.points(), black_box(t), interpolation));
+                    }
+                })
+            });
+
+            group.bench_with_input(
+                BenchmarkId::new("prepared", len),
+                &prepared,
+                |b, prepared| {
+                    b.iter(|| {
+                        for n in 0..SAMPLES {
+                            let t = n as f32 / (SAMPLES - 1) as f32;
+                            black_box(prepared.sample(black_box(t)));
+                        }
+                    })
+                },
+            );
+
+            // prepare cost is paid once per curve change (every frame in editor)
+            group.bench_with_input(BenchmarkId::new("prepare", len), &curve, |b, curve| {
+                b.iter(|| black_box(curve.prepare()))
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, sampling);
+criterion_main!(benches);
@@ -0,0 +1,401 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::spline::{segment, Segment};
+use crate::{Curve, Interpolation, Lut};
+
+/// Target language of generated source code
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    Rust,
+    Glsl,
+    Wgsl,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::Rust, Language::Glsl, Language::Wgsl];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Glsl => "glsl",
+            Language::Wgsl => "wgsl",
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL.iter().find(|l| l.name() == s) {
+            Some(l) => Ok(*l),
+            None => {
+                let names: Vec<&str> = Self::ALL.iter().map(|l| l.name()).collect();
+                Err(format!(
+                    "unknown language {}, expected {}",
+                    s,
+                    names.join("|")
+                ))
+            }
+        }
+    }
+}
+
+/// Self-contained function `name(t) -> value`, with points X and per segment
+/// polynomial coefficients (same segments as `sample`, so result is exactly the same).
+/// Bezier segments needs parameter solver, so bezier curves can be generated only as baked lut
+pub fn codegen(curve: &Curve, name: &str, language: Language) -> Result<String, String> {
+    if curve.interpolation() == Interpolation::Bezier {
+        return Err(String::from(
+            "bezier curve can`t be generated as polynomials, bake it to lut",
+        ));
+    }
+
+    let points = curve.points();
+    let name = identifier(name);
+    let header = format!(
+        "generated by rs-spline-editor: {} curve, {} points",
+        curve.interpolation(),
+        points.len()
+    );
+
+    // nothing to interpolate
+    if points.len() < 2 {
+        let value = float(points.first().map_or(0.0, |p| p.y));
+        return Ok(match language {
+            Language::Rust => format!(
+                "/// {}\npub fn {}(_t: f32) -> f32 {{\n    {}\n}}\n",
+                header, name, value
+            ),
+            Language::Glsl => format!(
+                "// {}\nfloat {}(float t) {{\n    return {};\n}}\n",
+                header, name, value
+            ),
+            Language::Wgsl => format!(
+                "// {}\nfn {}(t: f32) -> f32 {{\n    return {};\n}}\n",
+                header, name, value
+            ),
+        });
+    }
+
+    let xs: Vec<String> = points.iter().map(|p| float(p.x)).collect();
+    let coefficients: Vec<[String; 4]> = (0..points.len() - 1)
+        .map(|ind| match segment(points, ind, curve.interpolation()) {
+            Segment::Constant(y) => [float(y), float(0.0), float(0.0), float(0.0)],
+            Segment::Cubic(c) => c.map(float),
+            // bezier curve is rejected above
+            Segment::Bezier { .. } => unreachable!(),
+        })
+        .collect();
+
+    let n = xs.len();
+    let last = float(points[n - 1].y);
+    let xs = xs.join(", ");
+
+    let code = match language {
+        Language::Rust => {
+            let coefficients: Vec<String> = coefficients
+                .iter()
+                .map(|c| format!("        [{}],", c.join(", ")))
+                .collect();
+
+            format!(
+                "/// {header}
+pub fn {name}(t: f32) -> f32 {{
+    const XS: [f32; {n}] = [{xs}];
+    const COEFFS: [[f32; 4]; {segments}] = [
+{coefficients}
+    ];
+
+    if t <= XS[0] {{
+        return COEFFS[0][0];
+    }}
+    if t >= XS[{last_ind}] {{
+        return {last};
+    }}
+
+    // last point with x <= t, first one for NaN
+    let i = XS.partition_point(|x| *x <= t).saturating_sub(1);
+    let c = COEFFS[i];
+    let u = (t - XS[i]) / (XS[i + 1] - XS[i]);
+    ((c[3] * u + c[2]) * u + c[1]) * u + c[0]
+}}
+",
+                segments = n - 1,
+                last_ind = n - 1,
+                coefficients = coefficients.join("\n"),
+            )
+        }
+        Language::Glsl => {
+            let coefficients: Vec<String> = coefficients
+                .iter()
+                .map(|c| format!("        vec4({})", c.join(", ")))
+                .collect();
+
+            format!(
+                "// {header}
+float {name}(float t) {{
+    const float XS[{n}] = float[{n}]({xs});
+    const vec4 COEFFS[{segments}] = vec4[{segments}](
+{coefficients}
+    );
+
+    if (t <= XS[0]) {{
+        return COEFFS[0].x;
+    }}
+    if (t >= XS[{last_ind}]) {{
+        return {last};
+    }}
+
+    int i = 0;
+    for (int k = 1; k < {segments}; k++) {{
+        if (t >= XS[k]) {{
+            i = k;
+        }}
+    }}
+
+    vec4 c = COEFFS[i];
+    float u = (t - XS[i]) / (XS[i + 1] - XS[i]);
+    return ((c.w * u + c.z) * u + c.y) * u + c.x;
+}}
+",
+                segments = n - 1,
+                last_ind = n - 1,
+                coefficients = coefficients.join(",\n"),
+            )
+        }
+        Language::Wgsl => {
+            let coefficients: Vec<String> = coefficients
+                .iter()
+                .map(|c| format!("        vec4<f32>({})", c.join(", ")))
+                .collect();
+
+            format!(
+                "// {header}
+fn {name}(t: f32) -> f32 {{
+    var xs = array<f32, {n}>({xs});
+    var coeffs = array<vec4<f32>, {segments}>(
+{coefficients}
+    );
+
+    if (t <= xs[0]) {{
+        return coeffs[0].x;
+    }}
+    if (t >= xs[{last_ind}]) {{
+        return {last};
+    }}
+
+    var i = 0u;
+    for (var k = 1u; k < {segments}u; k++) {{
+        if (t >= xs[k]) {{
+            i = k;
+        }}
+    }}
+
+    let c = coeffs[i];
+    let u = (t - xs[i]) / (xs[i + 1u] - xs[i]);
+    return ((c.w * u + c.z) * u + c.y) * u + c.x;
+}}
+",
+                segments = n - 1,
+                last_ind = n - 1,
+                coefficients = coefficients.join(",\n"),
+            )
+        }
+    };
+
+    Ok(code)
+}
+
+/// Const array of lut values, with function `name(t) -> value` (linear interpolation, t clamped to 0..1)
+pub fn codegen_lut(lut: &Lut, name: &str, language: Language) -> String {
+    let name = identifier(name);
+
+    // lut of 0/1 values is never baked, but can be created manually
+    let mut values = lut.values().to_vec();
+    while values.len() < 2 {
+        values.push(values.last().copied().unwrap_or(0.0));
+    }
+
+    let n = values.len();
+    let header = format!("generated by rs-spline-editor: baked lut, {} values", n);
+    let values: Vec<String> = values.into_iter().map(float).collect();
+    let values: Vec<String> = values
+        .chunks(8)
+        .map(|chunk| format!("        {},", chunk.join(", ")))
+        .collect();
+    let values = values.join("\n");
+    let values = values.strip_suffix(',').unwrap_or(&values);
+
+    match language {
+        Language::Rust => format!(
+            "/// {header}
+pub fn {name}(t: f32) -> f32 {{
+    const LUT: [f32; {n}] = [
+{values}
+    ];
+
+    let pos = t.clamp(0.0, 1.0) * {last_ind}.0;
+    let i = (pos as usize).min({segments});
+    LUT[i] + (LUT[i + 1] - LUT[i]) * (pos - i as f32)
+}}
+",
+            last_ind = n - 1,
+            segments = n - 2,
+        ),
+        Language::Glsl => format!(
+            "// {header}
+float {name}(float t) {{
+    const float LUT[{n}] = float[{n}](
+{values}
+    );
+
+    float pos = clamp(t, 0.0, 1.0) * {last_ind}.0;
+    int i = min(int(pos), {segments});
+    return mix(LUT[i], LUT[i + 1], pos - float(i));
+}}
+",
+            last_ind = n - 1,
+            segments = n - 2,
+        ),
+        Language::Wgsl => format!(
+            "// {header}
+fn {name}(t: f32) -> f32 {{
+    var lut = array<f32, {n}>(
+{values}
+    );
+
+    let pos = clamp(t, 0.0, 1.0) * {last_ind}.0;
+    let i = min(u32(pos), {segments}u);
+    return mix(lut[i], lut[i + 1u], pos - f32(i));
+}}
+",
+            last_ind = n - 1,
+            segments = n - 2,
+        ),
+    }
+}
+
+/// snake_case identifier from any name (file name, ..), `curve` for empty
+pub fn identifier(name: &str) -> String {
+    let mut result = String::new();
+    for c in name.chars() {
+        match c.is_ascii_alphanumeric() {
+            true => result.push(c.to_ascii_lowercase()),
+            false if !result.ends_with('_') => result.push('_'),
+            false => {}
+        }
+    }
+
+    let result = result.trim_matches('_');
+    match result.chars().next() {
+        None => String::from("curve"),
+        Some(c) if c.is_ascii_digit() => format!("curve_{}", result),
+        Some(_) => String::from(result),
+    }
+}
+
+/// shortest exact f32 literal, always with dot (valid float in all languages)
+fn float(v: f32) -> String {
+    let s = format!("{:?}", v);
+    match s.contains('.') || s.contains('e') {
+        true => s,
+        false => format!("{}.0", s),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    pub fn codegen_rust_test() {
+        let curve = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)])
+            .with_interpolation(Interpolation::Linear);
+
+        assert_eq!(
+            codegen(&curve, "Ease In", Language::Rust).unwrap(),
+            "/// generated by rs-spline-editor: linear curve, 2 points
+pub fn ease_in(t: f32) -> f32 {
+    const XS: [f32; 2] = [0.0, 1.0];
+    const COEFFS: [[f32; 4]; 1] = [
+        [0.0, 1.0, 0.0, 0.0],
+    ];
+
+    if t <= XS[0] {
+        return COEFFS[0][0];
+    }
+    if t >= XS[1] {
+        return 1.0;
+    }
+
+    // last point with x <= t, first one for NaN
+    let i = XS.partition_point(|x| *x <= t).saturating_sub(1);
+    let c = COEFFS[i];
+    let u = (t - XS[i]) / (XS[i + 1] - XS[i]);
+    ((c[3] * u + c[2]) * u + c[1]) * u + c[0]
+}
+"
+        );
+    }
+
+    #[test]
+    pub fn codegen_shaders_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.5),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 0.25),
+        ]);
+
+        let glsl = codegen(&curve, "fade", Language::Glsl).unwrap();
+        assert!(glsl.contains("float fade(float t) {"));
+        assert!(glsl.contains("const float XS[3] = float[3](0.0, 0.5, 1.0);"));
+        assert!(glsl.contains("return 0.25;"));
+
+        let wgsl = codegen(&curve, "fade", Language::Wgsl).unwrap();
+        assert!(wgsl.contains("fn fade(t: f32) -> f32 {"));
+        assert!(wgsl.contains("var coeffs = array<vec4<f32>, 2>("));
+
+        let bezier = curve.with_interpolation(Interpolation::Bezier);
+        assert!(codegen(&bezier, "fade", Language::Wgsl).is_err());
+    }
+
+    #[test]
+    pub fn codegen_lut_test() {
+        let lut = Lut::new(vec![0.0, 0.5, 1.0]);
+
+        assert_eq!(
+            codegen_lut(&lut, "ramp", Language::Glsl),
+            "// generated by rs-spline-editor: baked lut, 3 values
+float ramp(float t) {
+    const float LUT[3] = float[3](
+        0.0, 0.5, 1.0
+    );
+
+    float pos = clamp(t, 0.0, 1.0) * 2.0;
+    int i = min(int(pos), 1);
+    return mix(LUT[i], LUT[i + 1], pos - float(i));
+}
+"
+        );
+        assert!(codegen_lut(&lut, "ramp", Language::Rust).contains("const LUT: [f32; 3] = ["));
+    }
+
+    #[test]
+    pub fn identifier_test() {
+        assert_eq!(identifier("simple"), "simple");
+        assert_eq!(identifier("Ease-In Out.curve"), "ease_in_out_curve");
+        assert_eq!(identifier("2d fade"), "curve_2d_fade");
+        assert_eq!(identifier("--"), "curve");
+        assert_eq!("wgsl".parse::<Language>().unwrap(), Language::Wgsl);
+    }
+}
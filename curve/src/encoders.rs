@@ -0,0 +1,1335 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engines::{decode_godot, decode_unity, encode_godot, encode_unity, is_godot, is_unity};
+use crate::web::{decode_svg, encode_svg};
+use crate::{Curve, Interpolation, Metadata, Point, Range, Tangent, TangentMode, DEFAULT_WEIGHT};
+
+const DELIMITER: &str = ":";
+const CSV_DELIMITER: &str = ",";
+const CSV_COLUMNS: [&str; 7] = [
+    "x",
+    "y",
+    "tangent",
+    "slope_in",
+    "slope_out",
+    "weight_in",
+    "weight_out",
+];
+const HEADER: &str = "#";
+const HEADER_VERSION: &str = "curve v";
+const HEADER_NAME: &str = "name";
+const HEADER_INTERPOLATION: &str = "interp";
+const HEADER_RANGE: &str = "range";
+
+/// version of single curve files, older files (without version line) are v1
+const VERSION: u32 = 2;
+/// latest format version: several curves in one file, every curve starts with version line
+const DOCUMENT_VERSION: u32 = 3;
+
+/// header block is written only for non default interpolation or metadata:
+/// `#curve v2`, then `#key=value` lines (name, interp, range, unknown keys).
+/// Tangent is written only for non auto points (`x:y:mode`, `x:y:free:in:out`),
+/// so plain catmull-rom curves is still `x:y` lines (readable by old versions).
+/// Weighted bezier handles are appended to free tangent (`x:y:free:in:out:win:wout`)
+pub fn encode(curve: &Curve) -> String {
+    let mut content = encode_header(curve, false);
+    content.push_str(&encode_points(curve));
+    content
+}
+
+fn encode_points(curve: &Curve) -> String {
+    let mut content = String::new();
+    for p in curve.points() {
+        content.push_str(format!("{:.4}{}{:.4}", p.x, DELIMITER, p.y).as_str());
+
+        match p.tangent.mode {
+            TangentMode::Auto => {}
+            TangentMode::Free => {
+                content.push_str(
+                    format!(
+                        "{}{}{}{:.4}{}{:.4}",
+                        DELIMITER,
+                        p.tangent.mode,
+                        DELIMITER,
+                        p.tangent.slope_in,
+                        DELIMITER,
+                        p.tangent.slope_out
+                    )
+                    .as_str(),
+                );
+
+                if p.tangent.weight_in != DEFAULT_WEIGHT || p.tangent.weight_out != DEFAULT_WEIGHT {
+                    content.push_str(
+                        format!(
+                            "{}{:.4}{}{:.4}",
+                            DELIMITER, p.tangent.weight_in, DELIMITER, p.tangent.weight_out
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+            mode => content.push_str(format!("{}{}", DELIMITER, mode).as_str()),
+        }
+
+        content.push('\n');
+    }
+
+    content
+}
+
+/// Headerless files (only `x:y` lines) are still valid.
+/// `#` lines that are not `#curve vN` or `#key=value` are comments, blank lines are skipped.
+/// First curve of document (see `decode_document`)
+pub fn decode(data: String) -> Result<Curve, String> {
+    let mut curves = decode_document(&data)?;
+    Ok(curves.swap_remove(0))
+}
+
+/// Several curves: `#curve v3` header block before points of every curve
+pub fn encode_document(curves: &[Curve]) -> String {
+    if let [curve] = curves {
+        return encode(curve);
+    }
+
+    let blocks: Vec<String> = curves
+        .iter()
+        .map(|curve| {
+            let mut content = encode_header(curve, true);
+            content.push_str(&encode_points(curve));
+            content
+        })
+        .collect();
+
+    blocks.join("\n")
+}
+
+/// All curves of file (at least one, maybe without points)
+pub fn decode_document(data: &str) -> Result<Vec<Curve>, String> {
+    blocks(data)
+        .iter()
+        .map(|lines| decode_lines(lines))
+        .collect()
+}
+
+/// numbered lines of every curve, next curve starts at version line after points or header keys
+fn blocks(data: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut blocks = vec![vec![]];
+    let mut has_data = false;
+
+    for (ind, line) in data.lines().enumerate() {
+        match line.strip_prefix(HEADER) {
+            Some(header) if header.starts_with(HEADER_VERSION) => {
+                if has_data {
+                    blocks.push(vec![]);
+                    has_data = false;
+                }
+            }
+            Some(header) => has_data |= matches!(decode_header(header), Ok(Some(_))),
+            None => has_data |= !line.trim().is_empty(),
+        }
+
+        blocks.last_mut().unwrap().push((ind + 1, line));
+    }
+
+    blocks
+}
+
+fn decode_lines(lines: &[(usize, &str)]) -> Result<Curve, String> {
+    let mut result = vec![];
+    let mut interpolation = Interpolation::default();
+    let mut metadata = Metadata::default();
+
+    for &(ind, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix(HEADER) {
+            let result = apply_header(header, &mut interpolation, &mut metadata);
+
+            if let Err(err) = result {
+                return Err(format!("line {}: {}", ind, err));
+            }
+            continue;
+        }
+
+        let p = line.split_once(DELIMITER);
+        if p.is_none() {
+            return Err(format!("line {}: unexpected data {}", ind, line));
+        }
+
+        let p = p.unwrap();
+        let (y, tangent) = match p.1.split_once(DELIMITER) {
+            Some((y, tangent)) => (y, Some(tangent)),
+            None => (p.1, None),
+        };
+
+        let x = match p.0.parse::<f32>() {
+            Ok(val) => val,
+            Err(err) => {
+                return Err(format!(
+                    "line {}: x is not float32: {} at {}",
+                    ind, err, line
+                ))
+            }
+        };
+        let y = match y.parse::<f32>() {
+            Ok(val) => val,
+            Err(err) => {
+                return Err(format!(
+                    "line {}: y is not float32: {} at {}",
+                    ind, err, line
+                ))
+            }
+        };
+
+        if !x.is_finite() || !y.is_finite() {
+            return Err(format!(
+                "line {}: x and y should be finite at {}",
+                ind, line
+            ));
+        }
+
+        let tangent = match tangent.map(decode_tangent) {
+            Some(Ok(tangent)) => tangent,
+            Some(Err(err)) => return Err(format!("line {}: {} at {}", ind, err, line)),
+            None => Tangent::default(),
+        };
+
+        result.push(Point::new(x, y).with_tangent(tangent));
+    }
+
+    Ok(Curve::new(result)
+        .with_interpolation(interpolation)
+        .with_metadata(metadata))
+}
+
+fn decode_tangent(data: &str) -> Result<Tangent, String> {
+    let mut parts = data.split(DELIMITER);
+    let mode = parts.next().unwrap_or_default().parse::<TangentMode>()?;
+
+    if mode != TangentMode::Free {
+        return match parts.next() {
+            Some(_) => Err(format!("unexpected data after tangent mode {}", mode)),
+            None => Ok(Tangent {
+                mode,
+                ..Tangent::default()
+            }),
+        };
+    }
+
+    let mut value = |name: &str, missing: &str| -> Result<Option<f32>, String> {
+        match parts.next() {
+            Some(val) => match val.parse::<f32>() {
+                Ok(v) if v.is_finite() => Ok(Some(v)),
+                Ok(v) => Err(format!("tangent {} should be finite, got {}", name, v)),
+                Err(err) => Err(format!("tangent {} is not float32: {}", name, err)),
+            },
+            None if missing.is_empty() => Ok(None),
+            None => Err(String::from(missing)),
+        }
+    };
+
+    let slope_missing = "free tangent expects in and out slopes";
+    let slope_in = value("slope", slope_missing)?.unwrap();
+    let slope_out = value("slope", slope_missing)?.unwrap();
+    let tangent = Tangent::free(slope_in, slope_out);
+
+    // optional bezier handles weights
+    let weight_in = match value("weight", "")? {
+        Some(val) => val,
+        None => return Ok(tangent),
+    };
+    let weight_out = value("weight", "free tangent expects in and out weights")?.unwrap();
+
+    if !(0.0..=1.0).contains(&weight_in) || !(0.0..=1.0).contains(&weight_out) {
+        return Err(String::from("tangent weight out of range 0.0..1.0"));
+    }
+    if parts.next().is_some() {
+        return Err(String::from("unexpected data after tangent weights"));
+    }
+
+    Ok(tangent.with_weights(weight_in, weight_out))
+}
+
+/// `#curve v2` header block, empty for default interpolation and metadata.
+/// Curve of document always has `#curve v3` header
+fn encode_header(curve: &Curve, document: bool) -> String {
+    let mut content = String::new();
+    let metadata = curve.metadata();
+    let version = match document {
+        true => DOCUMENT_VERSION,
+        false => VERSION,
+    };
+
+    if document || curve.interpolation() != Interpolation::default() || !metadata.is_empty() {
+        content.push_str(format!("{}{}{}\n", HEADER, HEADER_VERSION, version).as_str());
+
+        let mut header = |key: &str, value: String| {
+            content.push_str(format!("{}{}={}\n", HEADER, key, value).as_str());
+        };
+
+        if !metadata.name.is_empty() {
+            header(HEADER_NAME, metadata.name.clone());
+        }
+        if curve.interpolation() != Interpolation::default() {
+            header(HEADER_INTERPOLATION, curve.interpolation().to_string());
+        }
+        if let Some(range) = metadata.range {
+            header(HEADER_RANGE, range.to_string());
+        }
+        for (key, value) in metadata.extra.iter() {
+            header(key, value.clone());
+        }
+    }
+
+    content
+}
+
+/// parse header (or comment) line and store value
+fn apply_header(
+    header: &str,
+    interpolation: &mut Interpolation,
+    metadata: &mut Metadata,
+) -> Result<(), String> {
+    match decode_header(header)? {
+        Some((HEADER_INTERPOLATION, value)) => *interpolation = value.parse::<Interpolation>()?,
+        Some((HEADER_NAME, value)) => metadata.name = String::from(value),
+        Some((HEADER_RANGE, value)) => metadata.range = Some(decode_range(value)?),
+        Some((key, value)) => metadata
+            .extra
+            .push((String::from(key), String::from(value))),
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// `Some((key, value))` for `#key=value` line, None for version line and comments
+fn decode_header(header: &str) -> Result<Option<(&str, &str)>, String> {
+    if let Some(version) = header.strip_prefix(HEADER_VERSION) {
+        return match version.trim().parse::<u32>() {
+            Ok(val) if val <= DOCUMENT_VERSION => Ok(None),
+            Ok(val) => Err(format!(
+                "unsupported format version {}, expected {} or lower",
+                val, DOCUMENT_VERSION
+            )),
+            Err(err) => Err(format!("format version is not number: {}", err)),
+        };
+    }
+
+    // key is single word, otherwise line is just comment (like `# x=0 is start`)
+    match header.split_once('=') {
+        Some((key, value))
+            if !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+        {
+            Ok(Some((key, value.trim())))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// `x_min:x_max:y_min:y_max`
+pub(crate) fn decode_range(data: &str) -> Result<Range, String> {
+    let values: Vec<f32> = data
+        .split(DELIMITER)
+        .map(|v| v.trim().parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|err| format!("range is not float32: {}", err))?;
+
+    range_from(&values)
+}
+
+/// range of `x_min, x_max, y_min, y_max` values (also used by engines formats)
+pub(crate) fn range_from(values: &[f32]) -> Result<Range, String> {
+    if values.len() != 4 {
+        return Err(String::from("range expects x_min:x_max:y_min:y_max"));
+    }
+    // NaN fails min/max comparison below
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(String::from("range values should be finite"));
+    }
+
+    let range = Range {
+        x_min: values[0],
+        x_max: values[1],
+        y_min: values[2],
+        y_max: values[3],
+    };
+
+    if range.x_min >= range.x_max || range.y_min >= range.y_max {
+        return Err(String::from("range min should be less than max"));
+    }
+
+    Ok(range)
+}
+
+/// Supported text formats, chosen by file extension (or detected by content)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// native `x:y` lines
+    #[default]
+    Curve,
+    Json,
+    Ron,
+    /// `x,y` rows (with optional tangent columns) after `.curve` header lines
+    Csv,
+    /// unity `AnimationCurve` yaml
+    Unity,
+    /// godot `Curve` resource
+    Godot,
+    /// svg document with curve path (import fits points to any path)
+    Svg,
+}
+
+impl Format {
+    pub const ALL: [Format; 7] = [
+        Format::Curve,
+        Format::Json,
+        Format::Ron,
+        Format::Csv,
+        Format::Unity,
+        Format::Godot,
+        Format::Svg,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Curve => "curve",
+            Format::Json => "json",
+            Format::Ron => "ron",
+            Format::Csv => "csv",
+            Format::Unity => "unity",
+            Format::Godot => "godot",
+            Format::Svg => "svg",
+        }
+    }
+
+    /// file extensions, first one is used for saving
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Format::Unity => &["yaml", "yml", "asset", "anim"],
+            Format::Godot => &["tres"],
+            Format::Curve => &["curve"],
+            Format::Json => &["json"],
+            Format::Ron => &["ron"],
+            Format::Csv => &["csv"],
+            Format::Svg => &["svg"],
+        }
+    }
+
+    /// next format in ALL list (cycled)
+    pub fn next(&self) -> Self {
+        let ind = Self::ALL.iter().position(|f| f == self).unwrap();
+        Self::ALL[(ind + 1) % Self::ALL.len()]
+    }
+
+    /// by file extension (see `extensions`), None for unknown
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = std::path::Path::new(path).extension()?.to_str()?;
+        Self::ALL
+            .iter()
+            .find(|f| f.extensions().iter().any(|e| e.eq_ignore_ascii_case(ext)))
+            .copied()
+    }
+
+    /// guess format of text (pasted data, files with unknown extension)
+    pub fn detect(data: &str) -> Self {
+        if is_unity(data) {
+            return Format::Unity;
+        }
+        if is_godot(data) {
+            return Format::Godot;
+        }
+        // svg document, or only path data
+        let trimmed = data.trim_start();
+        if trimmed.starts_with('<') || trimmed.starts_with(['M', 'm']) {
+            return Format::Svg;
+        }
+
+        let first = data
+            .lines()
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty() && !l.starts_with(HEADER))
+            .unwrap_or_default();
+
+        match first.chars().next() {
+            Some('{') | Some('[') => Format::Json,
+            Some('(') => Format::Ron,
+            _ if first.contains(CSV_DELIMITER) && !first.contains(DELIMITER) => Format::Csv,
+            _ => Format::Curve,
+        }
+    }
+
+    pub fn encode(&self, curve: &Curve) -> String {
+        match self {
+            Format::Curve => encode(curve),
+            Format::Json => encode_json(curve),
+            Format::Ron => encode_ron(curve),
+            Format::Csv => encode_csv(curve),
+            Format::Unity => encode_unity(curve),
+            Format::Godot => encode_godot(curve),
+            Format::Svg => encode_svg(curve),
+        }
+    }
+
+    /// formats that can hold several curves (others have only active curve)
+    pub fn multiple(&self) -> bool {
+        matches!(
+            self,
+            Format::Curve | Format::Json | Format::Ron | Format::Csv
+        )
+    }
+
+    /// error when document has more curves than format can hold
+    pub fn check_document(&self, curves: &[Curve]) -> Result<(), String> {
+        match self.multiple() || curves.len() <= 1 {
+            true => Ok(()),
+            false => Err(format!(
+                "{} format has single curve, document has {} curves",
+                self,
+                curves.len()
+            )),
+        }
+    }
+
+    /// all curves (first one for single curve formats)
+    pub fn encode_all(&self, curves: &[Curve]) -> String {
+        match self {
+            Format::Curve => encode_document(curves),
+            Format::Json => encode_json_document(curves),
+            Format::Ron => encode_ron_document(curves),
+            Format::Csv => encode_csv_document(curves),
+            _ => self.encode(curves.first().unwrap_or(&Curve::default())),
+        }
+    }
+
+    /// at least one curve
+    pub fn decode_all(&self, data: &str) -> Result<Vec<Curve>, String> {
+        match self {
+            Format::Curve => decode_document(data),
+            Format::Json => decode_json_document(data),
+            Format::Ron => decode_ron_document(data),
+            Format::Csv => decode_csv_document(data),
+            _ => Ok(vec![self.decode(data)?]),
+        }
+    }
+
+    pub fn decode(&self, data: &str) -> Result<Curve, String> {
+        match self {
+            Format::Curve => decode(String::from(data)),
+            Format::Json => decode_json(data),
+            Format::Ron => decode_ron(data),
+            Format::Csv => decode_csv(data),
+            Format::Unity => decode_unity(data),
+            Format::Godot => decode_godot(data),
+            Format::Svg => decode_svg(data),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL.iter().find(|f| f.name() == s) {
+            Some(f) => Ok(*f),
+            None => {
+                let names: Vec<&str> = Self::ALL.iter().map(|f| f.name()).collect();
+                Err(format!(
+                    "unknown format {}, expected {}",
+                    s,
+                    names.join("|")
+                ))
+            }
+        }
+    }
+}
+
+/// decode with detected format
+pub fn decode_auto(data: &str) -> Result<Curve, String> {
+    Format::detect(data).decode(data)
+}
+
+/// json/ron schema, optional fields are skipped when empty
+#[derive(Serialize, Deserialize, Default)]
+struct CurveData {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    interp: String,
+    /// x_min, x_max, y_min, y_max
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    range: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extra: Vec<(String, String)>,
+    points: Vec<PointData>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PointData {
+    x: f32,
+    y: f32,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    tangent: String,
+    /// in, out (free tangent only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    slopes: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    weights: Vec<f32>,
+}
+
+/// several curves in one file
+#[derive(Serialize, Deserialize)]
+struct DocumentData {
+    curves: Vec<CurveData>,
+}
+
+/// json can be also plain array of points (`[{"x": 0.0, "y": 0.5}]`)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Document(DocumentData),
+    Curve(CurveData),
+    Points(Vec<PointData>),
+}
+
+fn to_data(curve: &Curve) -> CurveData {
+    let metadata = curve.metadata();
+
+    let points = curve
+        .points()
+        .iter()
+        .map(|p| {
+            let free = p.tangent.mode == TangentMode::Free;
+            let weighted =
+                p.tangent.weight_in != DEFAULT_WEIGHT || p.tangent.weight_out != DEFAULT_WEIGHT;
+
+            PointData {
+                x: p.x,
+                y: p.y,
+                tangent: match p.tangent.mode {
+                    TangentMode::Auto => String::new(),
+                    mode => mode.to_string(),
+                },
+                slopes: match free {
+                    true => vec![p.tangent.slope_in, p.tangent.slope_out],
+                    false => vec![],
+                },
+                weights: match free && weighted {
+                    true => vec![p.tangent.weight_in, p.tangent.weight_out],
+                    false => vec![],
+                },
+            }
+        })
+        .collect();
+
+    CurveData {
+        name: metadata.name.clone(),
+        interp: match curve.interpolation() == Interpolation::default() {
+            true => String::new(),
+            false => curve.interpolation().to_string(),
+        },
+        range: match metadata.range {
+            Some(r) => vec![r.x_min, r.x_max, r.y_min, r.y_max],
+            None => vec![],
+        },
+        extra: metadata.extra.clone(),
+        points,
+    }
+}
+
+fn from_data(data: CurveData) -> Result<Curve, String> {
+    let mut points = vec![];
+    for (ind, p) in data.points.iter().enumerate() {
+        if !p.x.is_finite() || !p.y.is_finite() {
+            return Err(format!("point {}: x and y should be finite", ind + 1));
+        }
+
+        let tangent = match p.tangent.as_str() {
+            "" => Ok(Tangent::default()),
+            mode => decode_tangent_values(mode, &p.slopes, &p.weights),
+        };
+
+        match tangent {
+            Ok(tangent) => points.push(Point::new(p.x, p.y).with_tangent(tangent)),
+            Err(err) => return Err(format!("point {}: {}", ind + 1, err)),
+        }
+    }
+
+    let interpolation = match data.interp.as_str() {
+        "" => Interpolation::default(),
+        interp => interp.parse::<Interpolation>()?,
+    };
+
+    let metadata = Metadata {
+        name: data.name,
+        range: match data.range.is_empty() {
+            true => None,
+            false => Some(range_from(&data.range)?),
+        },
+        extra: data.extra,
+    };
+
+    Ok(Curve::new(points)
+        .with_interpolation(interpolation)
+        .with_metadata(metadata))
+}
+
+/// tangent from mode name and optional slopes/weights values (json, ron, csv)
+fn decode_tangent_values(mode: &str, slopes: &[f32], weights: &[f32]) -> Result<Tangent, String> {
+    let mode = mode.parse::<TangentMode>()?;
+    if mode != TangentMode::Free {
+        return Ok(Tangent {
+            mode,
+            ..Tangent::default()
+        });
+    }
+
+    if slopes.len() != 2 {
+        return Err(String::from("free tangent expects in and out slopes"));
+    }
+    if slopes.iter().any(|s| !s.is_finite()) {
+        return Err(String::from("tangent slope should be finite"));
+    }
+
+    let tangent = Tangent::free(slopes[0], slopes[1]);
+    match weights.len() {
+        0 => Ok(tangent),
+        2 if weights.iter().all(|w| (0.0..=1.0).contains(w)) => {
+            Ok(tangent.with_weights(weights[0], weights[1]))
+        }
+        2 => Err(String::from("tangent weight out of range 0.0..1.0")),
+        _ => Err(String::from("free tangent expects in and out weights")),
+    }
+}
+
+pub fn encode_json(curve: &Curve) -> String {
+    let mut content = serde_json::to_string_pretty(&to_data(curve)).unwrap_or_default();
+    content.push('\n');
+    content
+}
+
+/// first curve of document
+pub fn decode_json(data: &str) -> Result<Curve, String> {
+    let mut curves = decode_json_document(data)?;
+    Ok(curves.swap_remove(0))
+}
+
+/// `{"curves": [..]}`, single curve is written as is
+fn encode_json_document(curves: &[Curve]) -> String {
+    if let [curve] = curves {
+        return encode_json(curve);
+    }
+
+    let data = DocumentData {
+        curves: curves.iter().map(to_data).collect(),
+    };
+    let mut content = serde_json::to_string_pretty(&data).unwrap_or_default();
+    content.push('\n');
+    content
+}
+
+fn decode_json_document(data: &str) -> Result<Vec<Curve>, String> {
+    match serde_json::from_str::<JsonData>(data) {
+        Ok(JsonData::Document(data)) => from_document(data),
+        Ok(JsonData::Curve(data)) => Ok(vec![from_data(data)?]),
+        Ok(JsonData::Points(points)) => Ok(vec![from_data(CurveData {
+            points,
+            ..CurveData::default()
+        })?]),
+        Err(err) => Err(format!("invalid json: {}", err)),
+    }
+}
+
+fn from_document(data: DocumentData) -> Result<Vec<Curve>, String> {
+    if data.curves.is_empty() {
+        return Err(String::from("document has no curves"));
+    }
+
+    data.curves
+        .into_iter()
+        .enumerate()
+        .map(|(ind, data)| from_data(data).map_err(|err| format!("curve {}: {}", ind + 1, err)))
+        .collect()
+}
+
+pub fn encode_ron(curve: &Curve) -> String {
+    // points in single line
+    let config = ron::ser::PrettyConfig::new()
+        .struct_names(false)
+        .depth_limit(2);
+    let mut content = ron::ser::to_string_pretty(&to_data(curve), config).unwrap_or_default();
+    content.push('\n');
+    content
+}
+
+/// first curve of document
+pub fn decode_ron(data: &str) -> Result<Curve, String> {
+    let mut curves = decode_ron_document(data)?;
+    Ok(curves.swap_remove(0))
+}
+
+fn encode_ron_document(curves: &[Curve]) -> String {
+    if let [curve] = curves {
+        return encode_ron(curve);
+    }
+
+    // points in single line, same as single curve
+    let config = ron::ser::PrettyConfig::new()
+        .struct_names(false)
+        .depth_limit(4);
+    let data = DocumentData {
+        curves: curves.iter().map(to_data).collect(),
+    };
+    let mut content = ron::ser::to_string_pretty(&data, config).unwrap_or_default();
+    content.push('\n');
+    content
+}
+
+fn decode_ron_document(data: &str) -> Result<Vec<Curve>, String> {
+    // document is checked first, error of single curve is more useful
+    if let Ok(document) = ron::from_str::<DocumentData>(data) {
+        return from_document(document);
+    }
+
+    let data = ron::from_str::<CurveData>(data).map_err(|err| format!("invalid ron: {}", err))?;
+    Ok(vec![from_data(data)?])
+}
+
+/// `.curve` header lines, then `x,y` rows.
+/// Tangent columns are written only when curve has non auto points
+pub fn encode_csv(curve: &Curve) -> String {
+    encode_csv_block(curve, false)
+}
+
+fn encode_csv_block(curve: &Curve, document: bool) -> String {
+    let mut content = encode_header(curve, document);
+    let tangents = curve
+        .points()
+        .iter()
+        .any(|p| p.tangent.mode != TangentMode::Auto);
+
+    let columns: &[&str] = match tangents {
+        true => &CSV_COLUMNS,
+        false => &CSV_COLUMNS[..2],
+    };
+    content.push_str(columns.join(CSV_DELIMITER).as_str());
+    content.push('\n');
+
+    for p in to_data(curve).points {
+        let value = |values: &[f32], ind: usize| match values.get(ind) {
+            Some(v) => format!("{:.4}", v),
+            None => String::new(),
+        };
+
+        let mut row = vec![format!("{:.4}", p.x), format!("{:.4}", p.y)];
+        if tangents {
+            row.push(p.tangent.clone());
+            row.push(value(&p.slopes, 0));
+            row.push(value(&p.slopes, 1));
+            row.push(value(&p.weights, 0));
+            row.push(value(&p.weights, 1));
+        }
+
+        content.push_str(row.join(CSV_DELIMITER).trim_end_matches(CSV_DELIMITER));
+        content.push('\n');
+    }
+
+    content
+}
+
+/// columns header row is optional (without it columns are in `CSV_COLUMNS` order).
+/// First curve of document
+pub fn decode_csv(data: &str) -> Result<Curve, String> {
+    let mut curves = decode_csv_document(data)?;
+    Ok(curves.swap_remove(0))
+}
+
+/// every curve is `.curve` header block with own rows (same as `encode_document`)
+fn encode_csv_document(curves: &[Curve]) -> String {
+    if let [curve] = curves {
+        return encode_csv(curve);
+    }
+
+    let blocks: Vec<String> = curves
+        .iter()
+        .map(|curve| encode_csv_block(curve, true))
+        .collect();
+    blocks.join("\n")
+}
+
+fn decode_csv_document(data: &str) -> Result<Vec<Curve>, String> {
+    blocks(data)
+        .iter()
+        .map(|lines| decode_csv_lines(lines))
+        .collect()
+}
+
+fn decode_csv_lines(lines: &[(usize, &str)]) -> Result<Curve, String> {
+    let mut result = vec![];
+    let mut interpolation = Interpolation::default();
+    let mut metadata = Metadata::default();
+    let mut columns: Option<Vec<String>> = None;
+
+    for &(ind, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix(HEADER) {
+            if let Err(err) = apply_header(header, &mut interpolation, &mut metadata) {
+                return Err(format!("line {}: {}", ind, err));
+            }
+            continue;
+        }
+
+        let cells: Vec<&str> = line.split(CSV_DELIMITER).map(|c| c.trim()).collect();
+
+        // columns names row
+        if columns.is_none() && result.is_empty() && cells[0].parse::<f32>().is_err() {
+            columns = Some(cells.iter().map(|c| c.to_lowercase()).collect());
+            continue;
+        }
+
+        let cell = |name: &str| -> &str {
+            let ind = match &columns {
+                Some(columns) => columns.iter().position(|c| c == name),
+                None => CSV_COLUMNS.iter().position(|c| *c == name),
+            };
+
+            ind.and_then(|ind| cells.get(ind))
+                .copied()
+                .unwrap_or_default()
+        };
+        let values = |names: [&str; 2]| -> Result<Vec<f32>, String> {
+            names
+                .iter()
+                .map(|name| cell(name))
+                .filter(|v| !v.is_empty())
+                .map(|v| {
+                    v.parse::<f32>()
+                        .map_err(|err| format!("{} is not float32: {} at {}", v, err, line))
+                })
+                .collect()
+        };
+
+        let point = match values(["x", "y"]) {
+            Ok(xy) if xy.len() == 2 && xy.iter().any(|v| !v.is_finite()) => {
+                Err(format!("x and y should be finite at {}", line))
+            }
+            Ok(xy) if xy.len() == 2 => Ok(Point::new(xy[0], xy[1])),
+            Ok(_) => Err(format!("expected x and y at {}", line)),
+            Err(err) => Err(err),
+        };
+
+        let tangent = match cell("tangent") {
+            "" => Ok(Tangent::default()),
+            mode => values(["slope_in", "slope_out"]).and_then(|slopes| {
+                values(["weight_in", "weight_out"]).and_then(|weights| {
+                    decode_tangent_values(mode, &slopes, &weights)
+                        .map_err(|err| format!("{} at {}", err, line))
+                })
+            }),
+        };
+
+        match (point, tangent) {
+            (Ok(p), Ok(tangent)) => result.push(p.with_tangent(tangent)),
+            (Err(err), _) | (_, Err(err)) => return Err(format!("line {}: {}", ind, err)),
+        }
+    }
+
+    Ok(Curve::new(result)
+        .with_interpolation(interpolation)
+        .with_metadata(metadata))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn encode_test() {
+        let data = vec![
+            Point::new(0.543, 0.7432),
+            Point::new(0.1, 0.99),
+            Point::new(0.45, 0.88),
+            Point::new(0.0, 0.05),
+            Point::new(1.0, 0.5345),
+        ];
+
+        assert_eq!(
+            encode(&Curve::new(data)),
+            String::from(
+                "0.0000:0.0500\n0.1000:0.9900\n0.4500:0.8800\n0.5430:0.7432\n1.0000:0.5345\n"
+            )
+        );
+    }
+
+    #[test]
+    pub fn encode_interpolation_test() {
+        let curve = Curve::new(vec![Point::new(0.0, 0.05), Point::new(1.0, 0.5345)])
+            .with_interpolation(Interpolation::Monotone);
+
+        assert_eq!(
+            encode(&curve),
+            String::from("#curve v2\n#interp=monotone\n0.0000:0.0500\n1.0000:0.5345\n")
+        );
+    }
+
+    #[test]
+    pub fn decode_test() {
+        let data = vec![
+            Point::new(0.0, 0.05),
+            Point::new(0.1, 0.99),
+            Point::new(0.45, 0.88),
+            Point::new(0.543, 0.7432),
+            Point::new(1.0, 0.5345),
+        ];
+
+        let decoded = decode(String::from(
+            "0.0000:0.0500\n0.1000:0.9900\n0.4500:0.8800\n0.5430:0.7432\n1.0000:0.5345\n",
+        ));
+        assert!(decoded.is_ok());
+        let decoded = decoded.unwrap();
+
+        assert_eq!(decoded.points(), data);
+        assert_eq!(decoded.interpolation(), Interpolation::CatmullRom);
+    }
+
+    #[test]
+    pub fn tangent_roundtrip_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.25, 0.5).with_tangent(Tangent {
+                mode: TangentMode::Constant,
+                ..Tangent::default()
+            }),
+            Point::new(0.5, 0.5).with_tangent(Tangent::free(-1.5, 2.0)),
+            Point::new(1.0, 1.0),
+        ]);
+
+        let encoded = encode(&curve);
+        assert_eq!(
+            encoded,
+            String::from(
+                "0.0000:0.0000\n0.2500:0.5000:constant\n0.5000:0.5000:free:-1.5000:2.0000\n1.0000:1.0000\n"
+            )
+        );
+        assert_eq!(decode(encoded).unwrap(), curve);
+
+        assert_eq!(
+            decode(String::from("0.5:0.5:free:1.0\n")).unwrap_err(),
+            "line 1: free tangent expects in and out slopes at 0.5:0.5:free:1.0"
+        );
+        assert_eq!(
+            decode(String::from("0.5:0.5:free:NaN:1.0\n")).unwrap_err(),
+            "line 1: tangent slope should be finite, got NaN at 0.5:0.5:free:NaN:1.0"
+        );
+        let ron = "(points: [(x: 0.5, y: 0.5, tangent: \"free\", slopes: [inf, 0.0])])";
+        assert_eq!(
+            decode_ron(ron).unwrap_err(),
+            "point 1: tangent slope should be finite"
+        );
+    }
+
+    #[test]
+    pub fn bezier_handles_roundtrip_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.5, 0.5).with_tangent(Tangent::free(0.0, 0.0).with_weights(0.75, 0.25)),
+            Point::new(1.0, 1.0).with_tangent(Tangent::free(1.0, 1.0)),
+        ])
+        .with_interpolation(Interpolation::Bezier);
+
+        let encoded = encode(&curve);
+        assert_eq!(
+            encoded,
+            String::from(
+                "#curve v2\n#interp=bezier\n0.0000:0.0000\n0.5000:0.5000:free:0.0000:0.0000:0.7500:0.2500\n1.0000:1.0000:free:1.0000:1.0000\n"
+            )
+        );
+        assert_eq!(decode(encoded).unwrap(), curve);
+
+        assert_eq!(
+            decode(String::from("0.5:0.5:free:0:0:0.5\n")).unwrap_err(),
+            "line 1: free tangent expects in and out weights at 0.5:0.5:free:0:0:0.5"
+        );
+        assert_eq!(
+            decode(String::from("0.5:0.5:free:0:0:0.5:1.5\n")).unwrap_err(),
+            "line 1: tangent weight out of range 0.0..1.0 at 0.5:0.5:free:0:0:0.5:1.5"
+        );
+    }
+
+    #[test]
+    pub fn decode_interpolation_test() {
+        let decoded = decode(String::from(
+            "#interp=constant\n0.0000:0.0500\n1.0000:0.5\n",
+        ));
+        assert!(decoded.is_ok());
+        assert_eq!(decoded.unwrap().interpolation(), Interpolation::Constant);
+
+        let decoded = decode(String::from("0.0000:0.0500\n#interp=cubic\n"));
+        assert_eq!(
+            decoded.unwrap_err(),
+            "line 2: unknown interpolation cubic, expected constant|linear|catmull-rom|monotone|bezier"
+        );
+    }
+
+    #[test]
+    pub fn header_roundtrip_test() {
+        let data = "#curve v2
+#name=ease in
+#interp=linear
+#range=0:2.5:-1:1
+#future=keep: me
+0.0000:0.0000
+1.0000:1.0000
+";
+
+        let decoded = decode(String::from(data)).unwrap();
+        assert_eq!(decoded.interpolation(), Interpolation::Linear);
+        assert_eq!(decoded.metadata().name, "ease in");
+        assert_eq!(
+            decoded.metadata().range,
+            Some(Range {
+                x_min: 0.0,
+                x_max: 2.5,
+                y_min: -1.0,
+                y_max: 1.0
+            })
+        );
+        assert_eq!(
+            decoded.metadata().extra,
+            vec![(String::from("future"), String::from("keep: me"))]
+        );
+
+        assert_eq!(encode(&decoded), data);
+    }
+
+    #[test]
+    pub fn comments_test() {
+        let decoded = decode(String::from(
+            "# exported from game\n\n#interp=constant\n0.0:0.5\n\n# x=1 is end\n1.0:0.5\n",
+        ))
+        .unwrap();
+
+        assert_eq!(decoded.points().len(), 2);
+        assert_eq!(decoded.interpolation(), Interpolation::Constant);
+        assert!(decoded.metadata().is_empty());
+
+        assert_eq!(
+            decode(String::from("#curve v4\n0.0:0.5\n")).unwrap_err(),
+            "line 1: unsupported format version 4, expected 3 or lower"
+        );
+        assert_eq!(
+            decode(String::from("\n#range=1:0:0:1\n")).unwrap_err(),
+            "line 2: range min should be less than max"
+        );
+    }
+
+    fn formats_curve() -> Curve {
+        Curve::new(vec![
+            Point::new(0.0, 0.5),
+            Point::new(0.25, 0.75).with_tangent(Tangent {
+                mode: TangentMode::Linear,
+                ..Tangent::default()
+            }),
+            Point::new(0.5, 0.25).with_tangent(Tangent::free(-1.5, 2.0).with_weights(0.5, 0.25)),
+            Point::new(1.0, 0.5),
+        ])
+        .with_interpolation(Interpolation::Bezier)
+        .with_metadata(Metadata {
+            name: String::from("ease"),
+            range: Some(Range {
+                x_min: 0.0,
+                x_max: 2.0,
+                y_min: 0.0,
+                y_max: 100.0,
+            }),
+            extra: vec![(String::from("future"), String::from("value"))],
+        })
+    }
+
+    #[test]
+    pub fn json_roundtrip_test() {
+        let curve = formats_curve();
+        let encoded = encode_json(&curve);
+
+        assert!(encoded.contains("\"interp\": \"bezier\""));
+        assert_eq!(Format::detect(&encoded), Format::Json);
+        assert_eq!(decode_json(&encoded).unwrap(), curve);
+
+        // plain points array
+        let plain = decode_json("[{\"x\": 0.0, \"y\": 0.5}, {\"x\": 1.0, \"y\": 0.25}]").unwrap();
+        assert_eq!(
+            plain.points(),
+            vec![Point::new(0.0, 0.5), Point::new(1.0, 0.25)]
+        );
+
+        assert_eq!(
+            decode_json("{\"points\": [{\"x\": 0.5, \"y\": 0.5, \"tangent\": \"free\"}]}")
+                .unwrap_err(),
+            "point 1: free tangent expects in and out slopes"
+        );
+    }
+
+    #[test]
+    pub fn ron_roundtrip_test() {
+        let curve = formats_curve();
+        let encoded = encode_ron(&curve);
+
+        assert!(encoded.starts_with("("));
+        assert_eq!(Format::detect(&encoded), Format::Ron);
+        assert_eq!(decode_ron(&encoded).unwrap(), curve);
+
+        let plain = decode_ron("(points: [(x: 0.0, y: 0.5), (x: 1.0, y: 0.25)])").unwrap();
+        assert_eq!(
+            plain,
+            Curve::new(vec![Point::new(0.0, 0.5), Point::new(1.0, 0.25)])
+        );
+    }
+
+    #[test]
+    pub fn csv_roundtrip_test() {
+        let curve = formats_curve();
+        let encoded = encode_csv(&curve);
+
+        assert_eq!(
+            encoded,
+            "#curve v2
+#name=ease
+#interp=bezier
+#range=0:2:0:100
+#future=value
+x,y,tangent,slope_in,slope_out,weight_in,weight_out
+0.0000,0.5000
+0.2500,0.7500,linear
+0.5000,0.2500,free,-1.5000,2.0000,0.5000,0.2500
+1.0000,0.5000
+"
+        );
+        assert_eq!(Format::detect(&encoded), Format::Csv);
+        assert_eq!(decode_csv(&encoded).unwrap(), curve);
+
+        // spreadsheet export: columns in any order, no header row
+        let plain = Curve::new(vec![Point::new(0.0, 0.5), Point::new(1.0, 0.25)]);
+        assert_eq!(encode_csv(&plain), "x,y\n0.0000,0.5000\n1.0000,0.2500\n");
+        assert_eq!(decode_csv("Y, X\n0.5, 0.0\n0.25, 1.0\n").unwrap(), plain);
+        assert_eq!(decode_csv("0.0,0.5\n1.0,0.25\n").unwrap(), plain);
+
+        assert_eq!(
+            decode_csv("x,y\n0.0,oups\n").unwrap_err(),
+            "line 2: oups is not float32: invalid float literal at 0.0,oups"
+        );
+    }
+
+    #[test]
+    pub fn document_test() {
+        let speed = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)]);
+        let fov = formats_curve();
+        let document = vec![speed.clone(), fov.clone(), Curve::default()];
+
+        let encoded = encode_document(&document);
+        assert!(encoded.starts_with("#curve v3\n0.0000:0.0000\n1.0000:1.0000\n\n#curve v3\n#name="));
+        assert_eq!(decode_document(&encoded).unwrap(), document);
+
+        for format in [Format::Curve, Format::Json, Format::Ron, Format::Csv] {
+            let decoded = format.decode_all(&format.encode_all(&document)).unwrap();
+            assert_eq!(decoded, document, "{} document", format);
+
+            // single curve is written as before, first curve is read by single curve decoder
+            assert_eq!(
+                format.encode_all(std::slice::from_ref(&fov)),
+                format.encode(&fov)
+            );
+            assert_eq!(format.decode(&format.encode_all(&document)).unwrap(), speed);
+            assert!(format.check_document(&document).is_ok());
+        }
+
+        // single curve formats can't hold document
+        for format in [Format::Unity, Format::Godot, Format::Svg] {
+            assert!(format.check_document(std::slice::from_ref(&fov)).is_ok());
+            let err = format.check_document(&document).unwrap_err();
+            assert!(err.ends_with("document has 3 curves"), "{}", err);
+        }
+
+        // comments before first version line are part of first curve
+        let decoded =
+            decode_document("# speed and fov\n#curve v3\n0:0\n#curve v3\n#name=fov\n").unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].metadata().name, "fov");
+        assert_eq!(decode_document("").unwrap(), vec![Curve::default()]);
+
+        assert_eq!(
+            decode_document("#curve v3\n0:0\n#curve v3\n0:oups\n").unwrap_err(),
+            "line 4: y is not float32: invalid float literal at 0:oups"
+        );
+        assert_eq!(
+            Format::Json.decode_all("{\"curves\": []}").unwrap_err(),
+            "document has no curves"
+        );
+    }
+
+    #[test]
+    pub fn format_detect_test() {
+        assert_eq!(Format::detect("0.0:0.5\n1.0:0.5\n"), Format::Curve);
+        assert_eq!(
+            Format::detect("#curve v2\n#interp=linear\n0.0:0.5\n"),
+            Format::Curve
+        );
+        assert_eq!(Format::detect("#interp=linear\nx,y\n"), Format::Csv);
+        assert_eq!(Format::detect("  [{\"x\": 0.0}]"), Format::Json);
+        assert_eq!(Format::detect(""), Format::Curve);
+        assert_eq!(Format::detect("curve:\n  m_Curve:\n"), Format::Unity);
+        assert_eq!(
+            Format::detect("[gd_resource type=\"Curve\" format=3]\n"),
+            Format::Godot
+        );
+        assert_eq!(Format::detect("M 0 100 C 10 0, 20 0, 100 0"), Format::Svg);
+        assert_eq!(
+            Format::detect("<?xml version=\"1.0\"?>\n<svg>"),
+            Format::Svg
+        );
+
+        assert_eq!(Format::from_path("dir/ease.RON"), Some(Format::Ron));
+        assert_eq!(Format::from_path("ease.txt"), None);
+        assert_eq!(Format::from_path("ease.tres"), Some(Format::Godot));
+        assert_eq!(Format::from_path("ease.anim"), Some(Format::Unity));
+        assert_eq!(
+            decode_auto("x,y\n0.0,0.5\n").unwrap(),
+            Curve::new(vec![Point::new(0.0, 0.5)])
+        );
+    }
+
+    #[test]
+    pub fn decode_err_test() {
+        let decoded = decode(String::from("0.0000:0.0500\n0.5:oups\n"));
+        assert!(decoded.is_err());
+
+        assert_eq!(
+            decoded.unwrap_err(),
+            "line 2: y is not float32: invalid float literal at 0.5:oups"
+        );
+
+        // parsed as float, but can`t be sorted or sampled
+        assert_eq!(
+            decode(String::from("0.0:0.0\nNaN:0.5\n")).unwrap_err(),
+            "line 2: x and y should be finite at NaN:0.5"
+        );
+        assert!(decode(String::from("0.5:inf\n")).is_err());
+        assert!(decode_csv("x,y\n0.0,0.0\nNaN,0.5\n").is_err());
+    }
+}
@@ -0,0 +1,752 @@
+//! Curves of game engines: Unity `AnimationCurve` (yaml snippet) and Godot `Curve` (`.tres`).
+//! Both are keyframes with explicit (in, out) tangent slopes, so points are imported as free tangents
+//! and exported with effective tangents of curve segments (catmull-rom, monotone, .. are all hermite).
+
+use crate::encoders::range_from;
+use crate::spline::{segment, Segment};
+use crate::{Curve, Interpolation, Metadata, Point, Range, Tangent, TangentMode, DEFAULT_WEIGHT};
+
+const UNITY_CURVE: &str = "m_Curve:";
+const UNITY_LIST_ITEM: &str = "- ";
+/// unity `TangentMode` bits: broken tangents, and constant right tangent
+const UNITY_BROKEN: u32 = 1;
+const UNITY_RIGHT_CONSTANT: u32 = 3 << 5;
+/// unity `WeightedMode`, both in and out weights are used
+const UNITY_WEIGHTED_BOTH: u32 = 3;
+
+const GODOT_HEADER: &str = "[gd_resource";
+const GODOT_DATA: &str = "_data";
+/// values of every godot point: position x, y, left and right tangents, left and right modes
+const GODOT_POINT_VALUES: usize = 6;
+
+/// Engine keyframe, in real units. Infinite out slope is step to next key (unity constant tangent)
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Key {
+    x: f32,
+    y: f32,
+    slope_in: f32,
+    slope_out: f32,
+    weight_in: f32,
+    weight_out: f32,
+}
+
+impl Default for Key {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            slope_in: 0.0,
+            slope_out: 0.0,
+            weight_in: DEFAULT_WEIGHT,
+            weight_out: DEFAULT_WEIGHT,
+        }
+    }
+}
+
+pub(crate) fn is_unity(data: &str) -> bool {
+    data.contains(UNITY_CURVE)
+}
+
+pub(crate) fn is_godot(data: &str) -> bool {
+    data.trim_start().starts_with(GODOT_HEADER) || data.contains("_data = [")
+}
+
+/// `curve:` field snippet (paste into scene/prefab/asset yaml),
+/// bezier curves are written as weighted keys
+pub fn encode_unity(curve: &Curve) -> String {
+    let weighted = match curve.interpolation() {
+        Interpolation::Bezier => UNITY_WEIGHTED_BOTH,
+        _ => 0,
+    };
+
+    let mut content = format!("curve:\n  serializedVersion: 2\n  {}\n", UNITY_CURVE);
+    for key in keys(curve) {
+        let mut mode = 0;
+        if key.slope_in != key.slope_out {
+            mode |= UNITY_BROKEN;
+        }
+        if key.slope_out.is_infinite() {
+            mode |= UNITY_RIGHT_CONSTANT;
+        }
+
+        content.push_str(
+            format!(
+                "  {}serializedVersion: 3
+    time: {}
+    value: {}
+    inSlope: {}
+    outSlope: {}
+    tangentMode: {}
+    weightedMode: {}
+    inWeight: {}
+    outWeight: {}
+",
+                UNITY_LIST_ITEM,
+                unity_float(key.x),
+                unity_float(key.y),
+                unity_float(key.slope_in),
+                unity_float(key.slope_out),
+                mode,
+                weighted,
+                unity_float(key.weight_in),
+                unity_float(key.weight_out),
+            )
+            .as_str(),
+        );
+    }
+
+    content.push_str("  m_PreInfinity: 2\n  m_PostInfinity: 2\n  m_RotationOrder: 4\n");
+    content
+}
+
+/// Keys of first `m_Curve` list (snippet, or whole `.anim`/`.asset` file).
+/// Weighted keys make bezier curve, infinite slopes are constant tangents.
+/// Range is keys bounds when keys are out of 0..1
+pub fn decode_unity(data: &str) -> Result<Curve, String> {
+    if !is_unity(data) {
+        return Err(format!("{} list is not found", UNITY_CURVE));
+    }
+
+    let mut keys: Vec<Key> = vec![];
+    // weightedMode of every key
+    let mut modes: Vec<u32> = vec![];
+    let mut list_indent: Option<usize> = None;
+
+    for (ind, line) in data.lines().enumerate() {
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        let list_indent = match list_indent {
+            Some(list_indent) => list_indent,
+            None => {
+                if let Some(rest) = trimmed.strip_prefix(UNITY_CURVE) {
+                    // flow style `m_Curve: []` is empty curve
+                    if !rest.trim().is_empty() {
+                        break;
+                    }
+                    list_indent = Some(indent);
+                }
+                continue;
+            }
+        };
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        // list is ended by next field of curve
+        let item = trimmed.strip_prefix(UNITY_LIST_ITEM);
+        if item.is_none() && indent <= list_indent {
+            break;
+        }
+        if item.is_some() {
+            keys.push(Key::default());
+            modes.push(0);
+        }
+
+        let field = item.unwrap_or(trimmed);
+        let keys_len = keys.len();
+        let (name, value, key) = match (field.split_once(':'), keys.last_mut()) {
+            (Some((name, value)), Some(key)) => (name.trim(), value.trim(), key),
+            _ => return Err(format!("line {}: unexpected data {}", ind + 1, line)),
+        };
+
+        let parse = |value: &str| {
+            value
+                .parse::<f32>()
+                .map_err(|err| format!("line {}: {} is not float32: {}", ind + 1, name, err))
+        };
+
+        // slopes can be infinite (constant tangents), but not points
+        let coord = |value: &str| match parse(value)? {
+            v if v.is_finite() => Ok(v),
+            _ => Err(format!("line {}: {} should be finite", ind + 1, name)),
+        };
+
+        match name {
+            "time" => key.x = coord(value)?,
+            "value" => key.y = coord(value)?,
+            "inSlope" => key.slope_in = parse(value)?,
+            "outSlope" => key.slope_out = parse(value)?,
+            "inWeight" => key.weight_in = parse(value)?,
+            "outWeight" => key.weight_out = parse(value)?,
+            "weightedMode" => {
+                let mode = value.parse::<u32>().map_err(|err| {
+                    format!("line {}: weightedMode is not number: {}", ind + 1, err)
+                })?;
+                modes[keys_len - 1] = mode;
+            }
+            // serializedVersion, tangentMode: tangents are always explicit
+            _ => {}
+        }
+    }
+
+    // not weighted side is same as default (hermite) handle
+    for (key, mode) in keys.iter_mut().zip(modes.iter()) {
+        if mode & 1 == 0 {
+            key.weight_in = DEFAULT_WEIGHT;
+        }
+        if mode & 2 == 0 {
+            key.weight_out = DEFAULT_WEIGHT;
+        }
+    }
+
+    let range = range_of(&keys);
+    let interpolation = match modes.iter().any(|mode| *mode != 0) {
+        true => Interpolation::Bezier,
+        false => Interpolation::default(),
+    };
+
+    Ok(Curve::new(points(&keys, range.unwrap_or_default()))
+        .with_interpolation(interpolation)
+        .with_metadata(Metadata {
+            range,
+            ..Metadata::default()
+        }))
+}
+
+/// Godot 4 `Curve` resource. Godot handles are always 1/3 of segment (weights are lost),
+/// and there is no step segments, constant segments are written flat
+pub fn encode_godot(curve: &Curve) -> String {
+    let range = curve.metadata().range.unwrap_or_default();
+    let default = Range::default();
+
+    let mut content = String::from("[gd_resource type=\"Curve\" format=3]\n\n[resource]\n");
+    if !curve.metadata().name.is_empty() {
+        content.push_str(format!("resource_name = {:?}\n", curve.metadata().name).as_str());
+    }
+    if range.x_min != default.x_min || range.x_max != default.x_max {
+        content.push_str(format!("min_domain = {:?}\n", range.x_min).as_str());
+        content.push_str(format!("max_domain = {:?}\n", range.x_max).as_str());
+    }
+    if range.y_min != default.y_min || range.y_max != default.y_max {
+        content.push_str(format!("min_value = {:?}\n", range.y_min).as_str());
+        content.push_str(format!("max_value = {:?}\n", range.y_max).as_str());
+    }
+
+    let finite = |slope: f32| match slope.is_finite() {
+        true => slope,
+        false => 0.0,
+    };
+
+    let keys = keys(curve);
+    let data: Vec<String> = keys
+        .iter()
+        .map(|key| {
+            format!(
+                "Vector2({:?}, {:?}), {:?}, {:?}, 0, 0",
+                key.x,
+                key.y,
+                finite(key.slope_in),
+                finite(key.slope_out)
+            )
+        })
+        .collect();
+
+    content.push_str(format!("{} = [{}]\n", GODOT_DATA, data.join(", ")).as_str());
+    content.push_str(format!("point_count = {}\n", keys.len()).as_str());
+    content
+}
+
+/// `[resource]` properties of Godot 3 or 4 `Curve`, `_data` array can be multiline
+pub fn decode_godot(data: &str) -> Result<Curve, String> {
+    let mut properties: Vec<(String, String)> = vec![];
+    // first line of points array (for errors)
+    let mut data_line = 0;
+
+    let mut lines = data.lines().enumerate();
+    while let Some((ind, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('[') || line.starts_with(';') {
+            continue;
+        }
+
+        let (name, value) = match line.split_once('=') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => return Err(format!("line {}: unexpected data {}", ind + 1, line)),
+        };
+
+        if name == GODOT_DATA {
+            data_line = ind + 1;
+        }
+
+        let mut value = String::from(value);
+        while value.starts_with('[') && !value.ends_with(']') {
+            match lines.next() {
+                Some((_, line)) => value.push_str(line.trim()),
+                None => return Err(format!("line {}: {} array is not closed", ind + 1, name)),
+            }
+        }
+
+        properties.push((String::from(name), value));
+    }
+
+    let property = |name: &str| {
+        properties
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let float = |name: &str, default: f32| match property(name) {
+        Some(value) => value
+            .parse::<f32>()
+            .map_err(|err| format!("{} is not float32: {}", name, err)),
+        None => Ok(default),
+    };
+
+    let values = match property(GODOT_DATA) {
+        Some(values) => values,
+        None => return Err(format!("{} of curve is not found", GODOT_DATA)),
+    };
+    let values = values
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .replace("Vector2(", "")
+        .replace(')', "");
+    let values = values
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<f32>()
+                .map_err(|err| format!("{} value {} is not float32: {}", GODOT_DATA, v, err))
+        })
+        .collect::<Result<Vec<f32>, String>>()?;
+
+    if values.len() % GODOT_POINT_VALUES != 0 {
+        return Err(format!(
+            "{} should have {} values per point, got {} values",
+            GODOT_DATA,
+            GODOT_POINT_VALUES,
+            values.len()
+        ));
+    }
+
+    let infinite = values
+        .chunks_exact(GODOT_POINT_VALUES)
+        .position(|v| !v[0].is_finite() || !v[1].is_finite());
+    if let Some(point) = infinite {
+        return Err(format!(
+            "line {}: {} point {} should be finite",
+            data_line,
+            GODOT_DATA,
+            point + 1
+        ));
+    }
+
+    let keys: Vec<Key> = values
+        .chunks_exact(GODOT_POINT_VALUES)
+        .map(|v| Key {
+            x: v[0],
+            y: v[1],
+            slope_in: v[2],
+            slope_out: v[3],
+            ..Key::default()
+        })
+        .collect();
+
+    let default = Range::default();
+    let range = range_from(&[
+        float("min_domain", default.x_min)?,
+        float("max_domain", default.x_max)?,
+        float("min_value", default.y_min)?,
+        float("max_value", default.y_max)?,
+    ])?;
+
+    let name = property("resource_name")
+        .map(|name| name.trim_matches('"'))
+        .unwrap_or_default();
+
+    Ok(Curve::new(points(&keys, range)).with_metadata(Metadata {
+        name: String::from(name),
+        range: (range != default).then_some(range),
+        ..Metadata::default()
+    }))
+}
+
+/// Keys (in real units) that are sampled same as curve: slopes and weights of every segment ends
+fn keys(curve: &Curve) -> Vec<Key> {
+    let points = curve.points();
+    let range = curve.metadata().range.unwrap_or_default();
+    let scale = range.slope_scale();
+
+    let mut keys: Vec<Key> = (0..points.len())
+        .map(|ind| {
+            let (slope_in, slope_out) = curve.slopes(ind);
+            Key {
+                x: points[ind].x,
+                y: points[ind].y,
+                slope_in,
+                slope_out,
+                ..Key::default()
+            }
+        })
+        .collect();
+
+    for ind in 0..points.len().saturating_sub(1) {
+        let width = points[ind + 1].x - points[ind].x;
+
+        match segment(points, ind, curve.interpolation()) {
+            Segment::Constant(_) => keys[ind].slope_out = f32::INFINITY,
+            Segment::Cubic(c) => {
+                keys[ind].slope_out = c[1] / width;
+                keys[ind + 1].slope_in = (c[1] + 2.0 * c[2] + 3.0 * c[3]) / width;
+            }
+            bezier => {
+                let [(_, p0), (x1, p1), (x2, p2), (_, p3)] = bezier.controls().unwrap();
+                let (w0, w1) = (x1, 1.0 - x2);
+
+                // zero length handle has no direction, any slope is the same
+                keys[ind].weight_out = w0;
+                if w0 > 0.0 {
+                    keys[ind].slope_out = (p1 - p0) / (w0 * width);
+                }
+                keys[ind + 1].weight_in = w1;
+                if w1 > 0.0 {
+                    keys[ind + 1].slope_in = (p3 - p2) / (w1 * width);
+                }
+            }
+        }
+    }
+
+    for key in keys.iter_mut() {
+        (key.x, key.y) = range.real(key.x, key.y);
+        key.slope_in *= scale;
+        key.slope_out *= scale;
+    }
+
+    keys
+}
+
+/// Free points from keys in real units. Constant (infinite) tangent is step after point,
+/// so in slope of constant point is lost (it is auto again)
+fn points(keys: &[Key], range: Range) -> Vec<Point> {
+    let scale = range.slope_scale();
+    let mut points: Vec<Point> = keys
+        .iter()
+        .map(|key| {
+            let (x, y) = range.normalized(key.x, key.y);
+            let tangent = Tangent::free(key.slope_in / scale, key.slope_out / scale)
+                .with_weights(key.weight_in, key.weight_out);
+            Point::new(x, y).with_tangent(tangent)
+        })
+        .collect();
+
+    for ind in 0..keys.len() {
+        let step_in = ind > 0 && keys[ind].slope_in.is_infinite();
+        if keys[ind].slope_out.is_infinite() {
+            points[ind].tangent = Tangent {
+                mode: TangentMode::Constant,
+                ..Tangent::default()
+            };
+        }
+        if step_in {
+            points[ind - 1].tangent = Tangent {
+                mode: TangentMode::Constant,
+                ..Tangent::default()
+            };
+        }
+    }
+
+    points
+}
+
+/// keys bounds (on axis with keys out of 0..1), None when keys are normalized already
+fn range_of(keys: &[Key]) -> Option<Range> {
+    let normalized = |v: f32| (0.0..=1.0).contains(&v);
+    if keys.iter().all(|k| normalized(k.x) && normalized(k.y)) {
+        return None;
+    }
+
+    let mut range = Range::default();
+    if keys.iter().any(|k| !normalized(k.x)) && keys.len() > 1 {
+        range.x_min = keys[0].x;
+        range.x_max = keys[keys.len() - 1].x;
+    }
+    for k in keys.iter() {
+        range.y_min = range.y_min.min(k.y);
+        range.y_max = range.y_max.max(k.y);
+    }
+
+    Some(range)
+}
+
+/// shortest exact float, unity style infinity
+fn unity_float(v: f32) -> String {
+    match v {
+        f32::INFINITY => String::from("Infinity"),
+        f32::NEG_INFINITY => String::from("-Infinity"),
+        v => format!("{}", v),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn curves() -> Vec<Curve> {
+        let points = vec![
+            Point::new(0.0, 0.5),
+            Point::new(0.2, 0.3),
+            Point::new(0.4, 0.5).with_tangent(Tangent::free(-1.0, 2.0)),
+            Point::new(0.6, 0.9),
+            Point::new(0.85, 0.05),
+            Point::new(1.0, 0.5),
+        ];
+        let curve = |i| Curve::new(points.clone()).with_interpolation(i);
+
+        let mut stepped = points.clone();
+        stepped[3].tangent.mode = TangentMode::Constant;
+        stepped[4].tangent.mode = TangentMode::Linear;
+
+        let mut weighted = points.clone();
+        weighted[2].tangent = Tangent::free(-1.0, 2.0).with_weights(0.8, 0.1);
+
+        vec![
+            curve(Interpolation::CatmullRom),
+            curve(Interpolation::Monotone),
+            curve(Interpolation::Linear),
+            curve(Interpolation::Bezier),
+            Curve::new(stepped),
+            Curve::new(weighted)
+                .with_interpolation(Interpolation::Bezier)
+                .with_metadata(Metadata {
+                    range: Some(Range {
+                        x_min: 0.0,
+                        x_max: 2.5,
+                        y_min: -1.0,
+                        y_max: 100.0,
+                    }),
+                    ..Metadata::default()
+                }),
+        ]
+    }
+
+    /// same values in real units (ranges of curves can be different)
+    fn assert_same_samples(a: &Curve, b: &Curve) {
+        let range_a = a.metadata().range.unwrap_or_default();
+        let range_b = b.metadata().range.unwrap_or_default();
+        let tolerance = 1e-4 * (range_a.y_max - range_a.y_min);
+
+        for n in 0..=1000 {
+            let x = n as f32 / 1000.0;
+            let (real_x, va) = range_a.real(x, a.sample(x));
+            let x_b = range_b.normalized(real_x, 0.0).0;
+            let vb = range_b.real(x_b, b.sample(x_b)).1;
+
+            assert!(
+                (va - vb).abs() < tolerance,
+                "{} curve, x={} {} != {}",
+                a.interpolation(),
+                real_x,
+                va,
+                vb
+            );
+        }
+    }
+
+    #[test]
+    pub fn unity_encode_test() {
+        let curve = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)])
+            .with_interpolation(Interpolation::Constant);
+
+        assert_eq!(
+            encode_unity(&curve),
+            "curve:
+  serializedVersion: 2
+  m_Curve:
+  - serializedVersion: 3
+    time: 0
+    value: 0
+    inSlope: 1
+    outSlope: Infinity
+    tangentMode: 97
+    weightedMode: 0
+    inWeight: 0.33333334
+    outWeight: 0.33333334
+  - serializedVersion: 3
+    time: 1
+    value: 1
+    inSlope: 1
+    outSlope: 1
+    tangentMode: 0
+    weightedMode: 0
+    inWeight: 0.33333334
+    outWeight: 0.33333334
+  m_PreInfinity: 2
+  m_PostInfinity: 2
+  m_RotationOrder: 4
+"
+        );
+    }
+
+    #[test]
+    pub fn unity_roundtrip_test() {
+        for curve in curves() {
+            let decoded = decode_unity(&encode_unity(&curve)).unwrap();
+
+            // unity has no range, bounds of keys are used instead
+            assert_eq!(decoded.points().len(), curve.points().len());
+            assert_same_samples(&curve, &decoded);
+        }
+    }
+
+    #[test]
+    pub fn unity_decode_test() {
+        // part of animation clip, as saved by unity editor
+        let data = "%YAML 1.1
+--- !u!74 &7400000
+AnimationClip:
+  m_FloatCurves:
+  - curve:
+      serializedVersion: 2
+      m_Curve:
+      - serializedVersion: 3
+        time: 0
+        value: 0
+        inSlope: 0
+        outSlope: 0
+        tangentMode: 0
+        weightedMode: 2
+        inWeight: 0
+        outWeight: 0.6
+      - serializedVersion: 3
+        time: 2
+        value: 10
+        inSlope: 5
+        outSlope: Infinity
+        tangentMode: 97
+        weightedMode: 0
+        inWeight: 0.33333334
+        outWeight: 0.33333334
+      - serializedVersion: 3
+        time: 4
+        value: 5
+        inSlope: 0
+        outSlope: 0
+        tangentMode: 0
+        weightedMode: 0
+        inWeight: 0.33333334
+        outWeight: 0.33333334
+      m_PreInfinity: 2
+      m_PostInfinity: 2
+      m_RotationOrder: 4
+    attribute: m_LocalPosition.y
+";
+        let curve = decode_unity(data).unwrap();
+        let points = curve.points();
+
+        assert_eq!(curve.interpolation(), Interpolation::Bezier);
+        assert_eq!(
+            curve.metadata().range,
+            Some(Range {
+                x_min: 0.0,
+                x_max: 4.0,
+                y_min: 0.0,
+                y_max: 10.0
+            })
+        );
+        assert_eq!(points.len(), 3);
+        assert_eq!((points[1].x, points[1].y), (0.5, 1.0));
+
+        // out weight only, in weight is default
+        assert_eq!(
+            points[0].tangent,
+            Tangent::free(0.0, 0.0).with_weights(DEFAULT_WEIGHT, 0.6)
+        );
+        assert_eq!(points[1].tangent.mode, TangentMode::Constant);
+        assert_eq!(curve.sample(0.75), 1.0);
+
+        assert_eq!(
+            decode_unity("curve:\n  m_Curve: []\n").unwrap(),
+            Curve::default()
+        );
+        assert_eq!(
+            decode_unity("m_Curve:\n- time: zero\n").unwrap_err(),
+            "line 2: time is not float32: invalid float literal"
+        );
+        assert_eq!(
+            decode_unity("m_Curve:\n- time: NaN\n  value: 0\n").unwrap_err(),
+            "line 2: time should be finite"
+        );
+        assert!(decode_unity("0.0:0.5\n").is_err());
+    }
+
+    #[test]
+    pub fn godot_encode_test() {
+        let curve = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.5)])
+            .with_interpolation(Interpolation::Linear)
+            .with_metadata(Metadata {
+                name: String::from("fade"),
+                range: Some(Range {
+                    y_max: 2.0,
+                    ..Range::default()
+                }),
+                ..Metadata::default()
+            });
+
+        assert_eq!(
+            encode_godot(&curve),
+            "[gd_resource type=\"Curve\" format=3]
+
+[resource]
+resource_name = \"fade\"
+min_value = 0.0
+max_value = 2.0
+_data = [Vector2(0.0, 0.0), 1.0, 1.0, 0, 0, Vector2(1.0, 1.0), 1.0, 1.0, 0, 0]
+point_count = 2
+"
+        );
+    }
+
+    #[test]
+    pub fn godot_roundtrip_test() {
+        // godot has no weights and steps
+        for curve in curves().into_iter().take(4) {
+            let decoded = decode_godot(&encode_godot(&curve)).unwrap();
+
+            assert_eq!(decoded.points().len(), curve.points().len());
+            assert_same_samples(&curve, &decoded);
+        }
+
+        let curve = curves().pop().unwrap();
+        let decoded = decode_godot(&encode_godot(&curve)).unwrap();
+        assert_eq!(decoded.metadata().range, curve.metadata().range);
+    }
+
+    #[test]
+    pub fn godot_decode_test() {
+        // godot 3 format, multiline data
+        let data = "[gd_resource type=\"Curve\" format=2]
+
+[resource]
+max_value = 2.0
+_data = [ Vector2( 0, 0 ), 0.0, 4.0, 0, 0,
+Vector2( 1, 2 ), 0.0, 0.0, 0, 0 ]
+";
+        let curve = decode_godot(data).unwrap();
+
+        assert_eq!(curve.points().len(), 2);
+        assert_eq!(curve.points()[1].y, 1.0);
+        assert_eq!(curve.slopes(0), (0.0, 2.0));
+        assert_eq!(curve.metadata().range.unwrap().y_max, 2.0);
+
+        assert_eq!(
+            decode_godot("[resource]\n_data = [Vector2(0, 0), 0.0]\n").unwrap_err(),
+            "_data should have 6 values per point, got 3 values"
+        );
+        assert_eq!(
+            decode_godot("[resource]\npoint_count = 0\n").unwrap_err(),
+            "_data of curve is not found"
+        );
+        assert_eq!(
+            decode_godot("[resource]\n_data = [Vector2(0, nan), 0.0, 0.0, 0, 0]\n").unwrap_err(),
+            "line 2: _data point 1 should be finite"
+        );
+        assert_eq!(
+            decode_godot("[resource]\nmax_value = nan\n_data = [Vector2(0, 0), 0.0, 0.0, 0, 0]\n")
+                .unwrap_err(),
+            "range values should be finite"
+        );
+    }
+}
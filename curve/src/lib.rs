@@ -0,0 +1,660 @@
+//! Curve sampling and `.curve` text format, shared by the editor and runtime code.
+//!
+//! No bevy here, points are plain `f32` pairs (with optional tangent).
+//! Enable `glam` feature for conversions from/to `glam::Vec2`.
+
+use std::fmt;
+use std::str::FromStr;
+
+mod codegen;
+mod encoders;
+mod engines;
+mod lut;
+mod prepared;
+mod spline;
+mod web;
+
+pub use codegen::{codegen, codegen_lut, identifier, Language};
+pub use encoders::{
+    decode, decode_auto, decode_csv, decode_document, decode_json, decode_ron, encode, encode_csv,
+    encode_document, encode_json, encode_ron, Format,
+};
+pub use engines::{decode_godot, decode_unity, encode_godot, encode_unity};
+pub use lut::Lut;
+pub use prepared::PreparedCurve;
+pub use spline::{lerp, sample, slopes};
+pub use web::{decode_svg, encode_css, encode_svg, encode_svg_path};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+    pub tangent: Tangent,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x,
+            y,
+            tangent: Tangent::default(),
+        }
+    }
+
+    pub fn with_tangent(mut self, tangent: Tangent) -> Self {
+        self.tangent = tangent;
+        self
+    }
+}
+
+/// Tangent mode of point (key), like in animation keyframe editors.
+/// Mode of left point defines segment after it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TangentMode {
+    /// tangents derived from curve interpolation
+    #[default]
+    Auto,
+    /// straight line to next point
+    Linear,
+    /// step, hold value until next point
+    Constant,
+    /// broken tangents, in/out slopes set explicitly
+    Free,
+}
+
+impl TangentMode {
+    pub const ALL: [TangentMode; 4] = [
+        TangentMode::Auto,
+        TangentMode::Linear,
+        TangentMode::Constant,
+        TangentMode::Free,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TangentMode::Auto => "auto",
+            TangentMode::Linear => "linear",
+            TangentMode::Constant => "constant",
+            TangentMode::Free => "free",
+        }
+    }
+
+    /// next mode in ALL list (cycled)
+    pub fn next(&self) -> Self {
+        let ind = Self::ALL.iter().position(|m| m == self).unwrap();
+        Self::ALL[(ind + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for TangentMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TangentMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL.iter().find(|m| m.name() == s) {
+            Some(m) => Ok(*m),
+            None => {
+                let names: Vec<&str> = Self::ALL.iter().map(|m| m.name()).collect();
+                Err(format!(
+                    "unknown tangent mode {}, expected {}",
+                    s,
+                    names.join("|")
+                ))
+            }
+        }
+    }
+}
+
+/// handle length (along X, part of segment width), when bezier segment is same as hermite
+pub const DEFAULT_WEIGHT: f32 = 1.0 / 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tangent {
+    pub mode: TangentMode,
+    /// slopes (dy/dx) of incoming and outgoing tangent, used only in Free mode
+    pub slope_in: f32,
+    pub slope_out: f32,
+    /// bezier handles length along X, part of previous/next segment width (0..1).
+    /// Used only in Free mode of bezier curve
+    pub weight_in: f32,
+    pub weight_out: f32,
+}
+
+impl Default for Tangent {
+    fn default() -> Self {
+        Self {
+            mode: TangentMode::Auto,
+            slope_in: 0.0,
+            slope_out: 0.0,
+            weight_in: DEFAULT_WEIGHT,
+            weight_out: DEFAULT_WEIGHT,
+        }
+    }
+}
+
+impl Tangent {
+    pub fn free(slope_in: f32, slope_out: f32) -> Self {
+        Self {
+            mode: TangentMode::Free,
+            slope_in,
+            slope_out,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_weights(mut self, weight_in: f32, weight_out: f32) -> Self {
+        self.weight_in = weight_in;
+        self.weight_out = weight_out;
+        self
+    }
+
+    /// effective (in, out) handle weights, clamped to 0..1,
+    /// so handles never cross neighbor points and X stays monotonic
+    pub fn weights(&self) -> (f32, f32) {
+        match self.mode {
+            TangentMode::Free => (
+                self.weight_in.clamp(0.0, 1.0),
+                self.weight_out.clamp(0.0, 1.0),
+            ),
+            _ => (DEFAULT_WEIGHT, DEFAULT_WEIGHT),
+        }
+    }
+}
+
+impl From<(f32, f32)> for Point {
+    fn from(v: (f32, f32)) -> Self {
+        Self::new(v.0, v.1)
+    }
+}
+
+impl From<Point> for (f32, f32) {
+    fn from(p: Point) -> Self {
+        (p.x, p.y)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::Vec2> for Point {
+    fn from(v: glam::Vec2) -> Self {
+        Self::new(v.x, v.y)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Point> for glam::Vec2 {
+    fn from(p: Point) -> Self {
+        glam::Vec2::new(p.x, p.y)
+    }
+}
+
+/// How values between points are calculated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// step, value of left point until next point
+    Constant,
+    Linear,
+    #[default]
+    CatmullRom,
+    /// cubic with Fritsch–Carlson tangents, never overshoots
+    Monotone,
+    /// cubic bezier, with (in, out) control handles on every point.
+    /// Auto handles are catmull-rom tangents, free ones can be weighted
+    Bezier,
+}
+
+impl Interpolation {
+    pub const ALL: [Interpolation; 5] = [
+        Interpolation::Constant,
+        Interpolation::Linear,
+        Interpolation::CatmullRom,
+        Interpolation::Monotone,
+        Interpolation::Bezier,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Interpolation::Constant => "constant",
+            Interpolation::Linear => "linear",
+            Interpolation::CatmullRom => "catmull-rom",
+            Interpolation::Monotone => "monotone",
+            Interpolation::Bezier => "bezier",
+        }
+    }
+
+    /// next mode in ALL list (cycled)
+    pub fn next(&self) -> Self {
+        let ind = Self::ALL.iter().position(|i| i == self).unwrap();
+        Self::ALL[(ind + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Interpolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Interpolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL.iter().find(|i| i.name() == s) {
+            Some(i) => Ok(*i),
+            None => {
+                let names: Vec<&str> = Self::ALL.iter().map(|i| i.name()).collect();
+                Err(format!(
+                    "unknown interpolation {}, expected {}",
+                    s,
+                    names.join("|")
+                ))
+            }
+        }
+    }
+}
+
+/// Real units of normalized (0..1) curve, like seconds on X and pixels on Y.
+/// Points are always stored normalized, range is only scale for display/export
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub x_min: f32,
+    pub x_max: f32,
+    pub y_min: f32,
+    pub y_max: f32,
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Self {
+            x_min: 0.0,
+            x_max: 1.0,
+            y_min: 0.0,
+            y_max: 1.0,
+        }
+    }
+}
+
+impl Range {
+    /// normalized (x, y) to real units
+    pub fn real(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            lerp(self.x_min, self.x_max, x),
+            lerp(self.y_min, self.y_max, y),
+        )
+    }
+
+    /// real units (x, y) to normalized
+    pub fn normalized(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.x_min) / (self.x_max - self.x_min),
+            (y - self.y_min) / (self.y_max - self.y_min),
+        )
+    }
+
+    /// multiplier of normalized slope (dy/dx) to slope in real units
+    pub fn slope_scale(&self) -> f32 {
+        (self.y_max - self.y_min) / (self.x_max - self.x_min)
+    }
+}
+
+/// `x_min:x_max:y_min:y_max`, same as `#range` header value
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.x_min, self.x_max, self.y_min, self.y_max
+        )
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        encoders::decode_range(s)
+    }
+}
+
+/// Optional `.curve` file header data, not used for sampling
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    pub name: String,
+    pub range: Option<Range>,
+    /// unknown header keys (written by newer versions), kept as is in original order
+    pub extra: Vec<(String, String)>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.range.is_none() && self.extra.is_empty()
+    }
+}
+
+/// Curve is list of points, always sorted by X axis.
+/// see `sample` for points data requirements
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Curve {
+    points: Vec<Point>,
+    interpolation: Interpolation,
+    metadata: Metadata,
+}
+
+impl Curve {
+    pub fn new(points: Vec<Point>) -> Self {
+        let mut points = points;
+        // total order, NaN can`t panic (decoders reject not finite values)
+        points.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        Self {
+            points,
+            interpolation: Interpolation::default(),
+            metadata: Metadata::default(),
+        }
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        sample(&self.points, t, self.interpolation)
+    }
+
+    /// cached segments with binary search lookup, for big curves or many samples
+    pub fn prepare(&self) -> PreparedCurve {
+        PreparedCurve::new(self)
+    }
+
+    /// lookup table with `size` evenly spaced samples, for runtime without spline math
+    pub fn bake(&self, size: usize) -> Lut {
+        Lut::bake(self, size)
+    }
+
+    /// effective (in, out) tangent slopes of point with index `ind`
+    pub fn slopes(&self, ind: usize) -> (f32, f32) {
+        slopes(&self.points, ind, self.interpolation)
+    }
+
+    pub fn encode(&self) -> String {
+        encode(self)
+    }
+
+    pub fn decode(data: &str) -> Result<Self, String> {
+        decode(String::from(data))
+    }
+
+    /// check that curve is usable for sampling (and editing):
+    /// starts at x=0, ends at x=1, values in 0..1, no duplicated x
+    pub fn validate(&self) -> Result<(), String> {
+        let first = match self.points.first() {
+            Some(p) => p,
+            None => return Err(String::from("curve has no points")),
+        };
+        let last = self.points.last().unwrap();
+
+        if first.x != 0.0 {
+            return Err(format!("curve should start at x=0.0, got {:.4}", first.x));
+        }
+        if last.x != 1.0 {
+            return Err(format!("curve should end at x=1.0, got {:.4}", last.x));
+        }
+
+        for (ind, p) in self.points.iter().enumerate() {
+            if !(0.0..=1.0).contains(&p.y) {
+                return Err(format!(
+                    "point {}: y={:.4} out of range 0.0..1.0",
+                    ind + 1,
+                    p.y
+                ));
+            }
+
+            if ind > 0 && self.points[ind - 1].x == p.x {
+                return Err(format!("point {}: duplicated x={:.4}", ind + 1, p.x));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn curve_sorted_test() {
+        let curve = Curve::new(vec![
+            Point::new(1.0, 0.5),
+            Point::new(0.0, 0.5),
+            Point::new(0.5, 0.75),
+        ]);
+
+        let xs: Vec<f32> = curve.points().iter().map(|p| p.x).collect();
+        assert_eq!(xs, vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    pub fn curve_roundtrip_test() {
+        let curve = Curve::decode(
+            "0.0000:0.5000\n0.4000:0.5000\n0.5000:0.7500\n0.6000:0.5000\n1.0000:0.5100\n",
+        )
+        .unwrap();
+
+        assert_eq!(Curve::decode(&curve.encode()).unwrap(), curve);
+        assert_eq!(curve.sample(0.0), 0.5);
+        assert_eq!(curve.sample(0.5), 0.75);
+        assert_eq!(curve.sample(1.0), 0.51);
+    }
+
+    #[test]
+    pub fn interpolation_modes_test() {
+        let points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 0.0),
+        ];
+        let curve = |i| Curve::new(points.clone()).with_interpolation(i);
+
+        assert_eq!(curve(Interpolation::Constant).sample(0.49), 0.0);
+        assert_eq!(curve(Interpolation::Constant).sample(0.5), 1.0);
+        assert_eq!(curve(Interpolation::Linear).sample(0.25), 0.5);
+        assert_eq!(curve(Interpolation::Linear).sample(0.75), 0.5);
+
+        // 3 points: middle point is not ignored anymore
+        assert!(curve(Interpolation::CatmullRom).sample(0.5) == 1.0);
+        assert!(curve(Interpolation::CatmullRom).sample(0.25) > 0.5);
+    }
+
+    #[test]
+    pub fn monotone_not_overshoot_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.1, 0.0),
+            Point::new(0.2, 1.0),
+            Point::new(0.9, 1.0),
+            Point::new(1.0, 0.5),
+        ])
+        .with_interpolation(Interpolation::Monotone);
+
+        let mut prev = curve.sample(0.0);
+        for n in 0..=1000 {
+            let x = n as f32 / 1000.0;
+            let value = curve.sample(x);
+            assert!((0.0..=1.0).contains(&value), "x={} value={}", x, value);
+
+            // monotonic between points
+            if x <= 0.9 {
+                assert!(value >= prev, "x={} value={} prev={}", x, value, prev);
+            }
+            prev = value;
+        }
+    }
+
+    #[test]
+    pub fn tangent_modes_test() {
+        let points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 0.0),
+        ];
+        let curve = |mode| {
+            let mut points = points.clone();
+            points[1].tangent.mode = mode;
+            Curve::new(points)
+        };
+
+        // only segment after point is affected
+        assert_eq!(curve(TangentMode::Constant).sample(0.75), 1.0);
+        assert_eq!(curve(TangentMode::Linear).sample(0.75), 0.5);
+        assert_eq!(
+            curve(TangentMode::Linear).sample(0.25),
+            curve(TangentMode::Auto).sample(0.25)
+        );
+    }
+
+    #[test]
+    pub fn free_tangent_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.5, 0.5).with_tangent(Tangent::free(0.0, 2.0)),
+            Point::new(1.0, 1.0),
+        ])
+        .with_interpolation(Interpolation::Linear);
+
+        assert_eq!(curve.slopes(1), (0.0, 2.0));
+
+        // arrives flat (so above linear before point), leaves steep (above linear after)
+        assert!(curve.sample(0.4) > 0.4);
+        assert!(curve.sample(0.6) > 0.6);
+        assert_eq!(curve.sample(0.5), 0.5);
+
+        // auto slopes of linear curve follows segments
+        assert_eq!(curve.slopes(0), (1.0, 1.0));
+    }
+
+    #[test]
+    pub fn bezier_test() {
+        let points = vec![
+            Point::new(0.0, 0.0).with_tangent(Tangent::free(0.0, 0.0)),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 0.0),
+        ];
+        let bezier = Curve::new(points.clone()).with_interpolation(Interpolation::Bezier);
+        let hermite = Curve::new(points.clone()).with_interpolation(Interpolation::CatmullRom);
+
+        // default handles (1/3 of segment) gives same curve as hermite
+        for n in 0..=20 {
+            let x = n as f32 / 20.0;
+            assert!(
+                (bezier.sample(x) - hermite.sample(x)).abs() < 1e-4,
+                "x={}",
+                x
+            );
+        }
+
+        // long flat out handle holds value near start longer
+        let mut weighted = points.clone();
+        weighted[0].tangent = weighted[0].tangent.with_weights(DEFAULT_WEIGHT, 1.0);
+        let weighted = Curve::new(weighted).with_interpolation(Interpolation::Bezier);
+        assert!(weighted.sample(0.1) < bezier.sample(0.1));
+        assert_eq!(weighted.sample(0.5), 1.0);
+
+        // out of range weights are clamped, so curve is still function of x
+        let mut broken = points;
+        broken[0].tangent = broken[0].tangent.with_weights(5.0, 5.0);
+        broken[1].tangent = Tangent::free(0.0, 0.0).with_weights(-2.0, 0.0);
+        let broken = Curve::new(broken).with_interpolation(Interpolation::Bezier);
+        for n in 0..=100 {
+            let value = broken.sample(n as f32 / 100.0);
+            assert!(value.is_finite() && (0.0..=1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    pub fn interpolation_cycle_test() {
+        let mut i = Interpolation::default();
+        for _ in 0..Interpolation::ALL.len() {
+            assert_eq!(i.name().parse::<Interpolation>().unwrap(), i);
+            i = i.next();
+        }
+
+        assert_eq!(i, Interpolation::default());
+    }
+
+    #[test]
+    pub fn range_test() {
+        let range = "0:30:-50:200".parse::<Range>().unwrap();
+        assert_eq!(range.to_string(), "0:30:-50:200");
+        assert_eq!(range.real(0.5, 0.2), (15.0, 0.0));
+        assert_eq!(range.normalized(15.0, 0.0), (0.5, 0.2));
+        assert_eq!(range.slope_scale(), 250.0 / 30.0);
+
+        assert!("0:30:50:-50".parse::<Range>().is_err());
+        assert!("0:30".parse::<Range>().is_err());
+
+        // NaN is not less or more than anything
+        assert_eq!(
+            "NaN:1:0:1".parse::<Range>().unwrap_err(),
+            "range values should be finite"
+        );
+        assert!("0:inf:0:1".parse::<Range>().is_err());
+        assert!(Curve::decode("#range=0:1:-inf:1\n0:0\n").is_err());
+    }
+
+    #[test]
+    pub fn validate_test() {
+        assert!(Curve::decode("0.0:0.5\n1.0:0.5\n")
+            .unwrap()
+            .validate()
+            .is_ok());
+
+        assert_eq!(
+            Curve::default().validate().unwrap_err(),
+            "curve has no points"
+        );
+        assert_eq!(
+            Curve::decode("0.1:0.5\n1.0:0.5\n")
+                .unwrap()
+                .validate()
+                .unwrap_err(),
+            "curve should start at x=0.0, got 0.1000"
+        );
+        assert_eq!(
+            Curve::decode("0.0:0.5\n0.5:1.5\n1.0:0.5\n")
+                .unwrap()
+                .validate()
+                .unwrap_err(),
+            "point 2: y=1.5000 out of range 0.0..1.0"
+        );
+        assert_eq!(
+            Curve::decode("0.0:0.5\n0.5:0.1\n0.5:0.2\n1.0:0.5\n")
+                .unwrap()
+                .validate()
+                .unwrap_err(),
+            "point 3: duplicated x=0.5000"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::Curve;
+
+/// sub samples between two entries, used for error metric
+const ERROR_SUBSAMPLES: usize = 16;
+
+/// Lookup table, evenly spaced curve samples on x 0..1 (first and last included).
+/// Runtime sampling is linear interpolation between entries, no spline math.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lut {
+    values: Vec<f32>,
+}
+
+impl Lut {
+    pub fn new(values: Vec<f32>) -> Self {
+        Self { values }
+    }
+
+    /// `size` entries (at least 2)
+    pub fn bake(curve: &Curve, size: usize) -> Self {
+        let size = size.max(2);
+        let prepared = curve.prepare();
+
+        let values = (0..size)
+            .map(|n| prepared.sample(n as f32 / (size - 1) as f32))
+            .collect();
+
+        Self { values }
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// t clamped to 0..1
+    pub fn sample(&self, t: f32) -> f32 {
+        match self.values.len() {
+            0 => return 0.0,
+            1 => return self.values[0],
+            _ => {}
+        }
+
+        let last_ind = self.values.len() - 1;
+        let pos = t.clamp(0.0, 1.0) * last_ind as f32;
+        let ind = (pos as usize).min(last_ind - 1);
+
+        let a = self.values[ind];
+        let b = self.values[ind + 1];
+        a + ((b - a) * (pos - ind as f32))
+    }
+
+    /// max deviation |lut - curve|, checked between every two entries
+    pub fn max_error(&self, curve: &Curve) -> f32 {
+        let prepared = curve.prepare();
+        let steps = self.values.len().max(2) * ERROR_SUBSAMPLES;
+
+        (0..=steps)
+            .map(|n| {
+                let x = n as f32 / steps as f32;
+                (self.sample(x) - prepared.sample(x)).abs()
+            })
+            .fold(0.0, f32::max)
+    }
+
+    /// raw f32 little-endian values, without header
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() % 4 != 0 {
+            return Err(format!(
+                "lut data size {} is not multiple of 4 (f32)",
+                data.len()
+            ));
+        }
+
+        let values = data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        Ok(Self { values })
+    }
+
+    /// one value per line
+    pub fn to_text(&self) -> String {
+        let mut content = String::new();
+        for v in self.values.iter() {
+            content.push_str(format!("{:.6}\n", v).as_str());
+        }
+
+        content
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Interpolation, Point};
+
+    fn curve() -> Curve {
+        Curve::new(vec![
+            Point::new(0.0, 0.5),
+            Point::new(0.2, 0.3),
+            Point::new(0.4, 0.5),
+            Point::new(0.8, 0.9),
+            Point::new(0.85, 0.05),
+            Point::new(1.0, 0.5),
+        ])
+    }
+
+    #[test]
+    pub fn bake_test() {
+        let curve = curve();
+        let lut = curve.bake(5);
+
+        assert_eq!(lut.len(), 5);
+        assert_eq!(lut.values()[0], curve.sample(0.0));
+        assert_eq!(lut.values()[2], curve.sample(0.5));
+        assert_eq!(lut.values()[4], curve.sample(1.0));
+
+        // linear between entries
+        let mid = (lut.values()[0] + lut.values()[1]) * 0.5;
+        assert!((lut.sample(0.125) - mid).abs() < 1e-6);
+
+        // clamped
+        assert_eq!(lut.sample(-1.0), lut.values()[0]);
+        assert_eq!(lut.sample(2.0), lut.values()[4]);
+        assert_eq!(curve.bake(0).len(), 2);
+    }
+
+    #[test]
+    pub fn max_error_test() {
+        let curve = curve();
+
+        let coarse = curve.bake(16).max_error(&curve);
+        let fine = curve.bake(1024).max_error(&curve);
+        assert!(fine < coarse, "fine={} coarse={}", fine, coarse);
+        assert!(fine < 0.001, "fine={}", fine);
+
+        // linear curve is exact with any size
+        let linear = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)])
+            .with_interpolation(Interpolation::Linear);
+        assert!(linear.bake(2).max_error(&linear) < 1e-6);
+    }
+
+    #[test]
+    pub fn bytes_roundtrip_test() {
+        let lut = curve().bake(64);
+        let bytes = lut.to_bytes();
+
+        assert_eq!(bytes.len(), 64 * 4);
+        assert_eq!(&bytes[0..4], &0.5f32.to_le_bytes());
+        assert_eq!(Lut::from_bytes(&bytes).unwrap(), lut);
+
+        assert_eq!(
+            Lut::from_bytes(&bytes[0..5]).unwrap_err(),
+            "lut data size 5 is not multiple of 4 (f32)"
+        );
+        assert_eq!(Lut::new(vec![0.5, 0.25]).to_text(), "0.500000\n0.250000\n");
+    }
+}
@@ -0,0 +1,120 @@
+use crate::spline::{segment, Segment};
+use crate::Curve;
+
+/// Curve ready for many samples: points sorted once, every segment
+/// coefficients precomputed, segment lookup is binary search (O(log N)).
+/// Immutable, prepare it again after curve change.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreparedCurve {
+    xs: Vec<f32>,
+    ys: Vec<f32>,
+    segments: Vec<Segment>,
+}
+
+impl PreparedCurve {
+    pub fn new(curve: &Curve) -> Self {
+        let points = curve.points();
+        let segments = (0..points.len().saturating_sub(1))
+            .map(|ind| segment(points, ind, curve.interpolation()))
+            .collect();
+
+        Self {
+            xs: points.iter().map(|p| p.x).collect(),
+            ys: points.iter().map(|p| p.y).collect(),
+            segments,
+        }
+    }
+
+    /// same result as `sample` of source curve
+    pub fn sample(&self, t: f32) -> f32 {
+        if self.xs.is_empty() {
+            return 0.0;
+        }
+
+        // single point is constant, even for NaN (it is in no segment)
+        let last_ind = self.xs.len() - 1;
+        if t <= self.xs[0] || last_ind == 0 {
+            return self.ys[0];
+        }
+        if t >= self.xs[last_ind] {
+            return self.ys[last_ind];
+        }
+
+        // last point with x <= t, first one for NaN
+        let ind = self.xs.partition_point(|x| *x <= t).saturating_sub(1);
+        let norm_x = (t - self.xs[ind]) / (self.xs[ind + 1] - self.xs[ind]);
+
+        self.segments[ind].sample(norm_x)
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Interpolation, Point, Tangent, TangentMode};
+
+    #[test]
+    pub fn same_as_sample_test() {
+        let points = vec![
+            Point::new(0.0, 0.5),
+            Point::new(0.2, 0.3).with_tangent(Tangent::free(2.0, -1.0).with_weights(0.1, 0.8)),
+            Point::new(0.4, 0.5),
+            Point::new(0.5, 0.1).with_tangent(Tangent {
+                mode: TangentMode::Constant,
+                ..Tangent::default()
+            }),
+            Point::new(0.8, 0.9).with_tangent(Tangent {
+                mode: TangentMode::Linear,
+                ..Tangent::default()
+            }),
+            Point::new(0.85, 0.05),
+            Point::new(1.0, 0.5),
+        ];
+
+        for interpolation in Interpolation::ALL {
+            let curve = Curve::new(points.clone()).with_interpolation(interpolation);
+            let prepared = curve.prepare();
+
+            for n in 0..=1000 {
+                let x = n as f32 / 1000.0;
+                assert_eq!(
+                    prepared.sample(x),
+                    curve.sample(x),
+                    "{} x={}",
+                    interpolation,
+                    x
+                );
+            }
+
+            // out of range
+            assert_eq!(prepared.sample(-1.0), 0.5);
+            assert_eq!(prepared.sample(2.0), 0.5);
+        }
+    }
+
+    #[test]
+    pub fn small_curves_test() {
+        assert_eq!(Curve::default().prepare().sample(0.5), 0.0);
+        assert!(Curve::default().prepare().is_empty());
+
+        let single = Curve::new(vec![Point::new(0.5, 0.7)]).prepare();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single.sample(0.0), 0.7);
+        assert_eq!(single.sample(1.0), 0.7);
+
+        // NaN is not in any segment, it is not a panic
+        let linear = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)]).prepare();
+        assert!(linear.sample(f32::NAN).is_nan());
+        assert_eq!(single.sample(f32::NAN), 0.7);
+        let curve = Curve::new(vec![Point::new(0.5, 0.7)]);
+        assert_eq!(curve.sample(f32::NAN), 0.7);
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{Interpolation, Point, TangentMode};
+
+/// O(N)
+///
+/// sample will return interpolated point on spline defined in points
+/// points data requirements:
+/// - all points sorted by X axis
+/// - all values (x,y) clamped between 0.0 and 1.0 (real units are only `Metadata::range` scale)
+/// - t clamped between 0.0 and 1.0
+/// - spline len should be not so big ~15-20 points is ok (alg not implement binary search and working with O(N)),
+///   for bigger curves or many samples use `Curve::prepare` (O(log N), cached segments)
+/// - result depending on array size:
+///     len(0) = 0.0
+///     len(1) = value of this single point
+///     len(2+) = interpolated by selected mode (catmull-rom with 2 points is linear lerp)
+/// - bezier segments are y=f(x), parameter is solved for x on every sample
+/// - tangent mode of left point can override segment interpolation (see `TangentMode`)
+pub fn sample(points: &[Point], t: f32, interpolation: Interpolation) -> f32 {
+    if points.is_empty() {
+        return 0.0;
+    }
+
+    let last_ind = points.len() - 1;
+
+    // micro opts (single point is constant, even for NaN)
+    if t <= points[0].x || last_ind == 0 {
+        return points[0].y;
+    }
+    if t >= points[last_ind].x {
+        return points[last_ind].y;
+    }
+
+    // alg
+    let ind = lowest(points, t);
+    let norm_x = normalize(t, points[ind].x, points[ind + 1].x);
+
+    segment(points, ind, interpolation).sample(norm_x)
+}
+
+/// Segment between points ind and ind+1, as polynomial of normalized x (0..1).
+/// Coefficients are calculated once, so it can be cached (see `PreparedCurve`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Segment {
+    Constant(f32),
+    /// c0 + c1*t + c2*t^2 + c3*t^3 (linear and hermite segments)
+    Cubic([f32; 4]),
+    /// bezier Y polynomial of parameter u, and X control points,
+    /// u is solved for normalized x on every sample
+    Bezier {
+        y: [f32; 4],
+        x1: f32,
+        x2: f32,
+    },
+}
+
+impl Segment {
+    #[inline(always)]
+    pub(crate) fn sample(&self, norm_x: f32) -> f32 {
+        match self {
+            Segment::Constant(y) => *y,
+            Segment::Cubic(c) => polynomial(c, norm_x),
+            Segment::Bezier { y, x1, x2 } => polynomial(y, solve_bezier(norm_x, *x1, *x2)),
+        }
+    }
+
+    /// cubic bezier control points (x normalized, y absolute), None for step
+    pub(crate) fn controls(&self) -> Option<[(f32, f32); 4]> {
+        let (y, x1, x2) = match self {
+            Segment::Constant(_) => return None,
+            // x(u) = u, handles at thirds
+            Segment::Cubic(c) => (c, 1.0 / 3.0, 2.0 / 3.0),
+            Segment::Bezier { y, x1, x2 } => (y, *x1, *x2),
+        };
+
+        // inverse of bezier_coefficients
+        let p1 = y[0] + y[1] / 3.0;
+        let p2 = y[2] / 3.0 - y[0] + 2.0 * p1;
+        let p3 = y[0] + y[1] + y[2] + y[3];
+
+        Some([(0.0, y[0]), (x1, p1), (x2, p2), (1.0, p3)])
+    }
+}
+
+pub(crate) fn segment(points: &[Point], ind: usize, interpolation: Interpolation) -> Segment {
+    let cp0 = points[ind];
+    let cp1 = points[ind + 1];
+
+    match cp0.tangent.mode {
+        TangentMode::Constant => return Segment::Constant(cp0.y),
+        TangentMode::Linear => return linear(cp0.y, cp1.y),
+        _ => {}
+    }
+
+    // explicit tangent on any side, segment is always cubic
+    let free = cp0.tangent.mode == TangentMode::Free || cp1.tangent.mode == TangentMode::Free;
+
+    match interpolation {
+        Interpolation::Bezier => {
+            let width = cp1.x - cp0.x;
+            let m0 = slopes(points, ind, interpolation).1;
+            let m1 = slopes(points, ind + 1, interpolation).0;
+            let w0 = cp0.tangent.weights().1;
+            let w1 = cp1.tangent.weights().0;
+
+            // control points in segment space (x normalized, y absolute)
+            let y1 = cp0.y + m0 * w0 * width;
+            let y2 = cp1.y - m1 * w1 * width;
+
+            Segment::Bezier {
+                y: bezier_coefficients(cp0.y, y1, y2, cp1.y),
+                x1: w0,
+                x2: 1.0 - w1,
+            }
+        }
+        Interpolation::Constant if !free => Segment::Constant(cp0.y),
+        Interpolation::Linear if !free => linear(cp0.y, cp1.y),
+        Interpolation::CatmullRom if !free && points.len() == 2 => linear(cp0.y, cp1.y),
+        _ => {
+            let width = cp1.x - cp0.x;
+            let m0 = slopes(points, ind, interpolation).1 * width;
+            let m1 = slopes(points, ind + 1, interpolation).0 * width;
+
+            Segment::Cubic(hermite_coefficients(cp0.y, cp1.y, m0, m1))
+        }
+    }
+}
+
+/// (in, out) tangent slopes (dy/dx) of point k.
+/// Free points use own slopes, others derived from curve interpolation.
+pub fn slopes(points: &[Point], k: usize, interpolation: Interpolation) -> (f32, f32) {
+    let p = points[k];
+    if p.tangent.mode == TangentMode::Free {
+        return (p.tangent.slope_in, p.tangent.slope_out);
+    }
+
+    match interpolation {
+        Interpolation::CatmullRom | Interpolation::Bezier => {
+            let m = catmull_rom_tangent(points, k);
+            (m, m)
+        }
+        Interpolation::Monotone => {
+            let m = monotone_tangent(points, k);
+            (m, m)
+        }
+        Interpolation::Constant | Interpolation::Linear => {
+            let (left, right) = secants(points, k);
+            match (left, right) {
+                (Some(d0), Some(d1)) => (d0, d1),
+                (Some(d), None) | (None, Some(d)) => (d, d),
+                (None, None) => (0.0, 0.0),
+            }
+        }
+    }
+}
+
+#[inline(always)]
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + ((b - a) * t)
+}
+
+#[inline(always)]
+fn lowest(points: &[Point], t: f32) -> usize {
+    let mut ind = points.len() - 1;
+    for p in points.iter().rev() {
+        if t >= p.x {
+            return ind;
+        }
+
+        if ind == 0 {
+            break;
+        }
+
+        ind -= 1;
+    }
+
+    return 0;
+}
+
+#[inline(always)]
+fn normalize(t: f32, start: f32, end: f32) -> f32 {
+    (t - start) / (end - start)
+}
+
+#[inline(always)]
+fn linear(a: f32, b: f32) -> Segment {
+    Segment::Cubic([a, b - a, 0.0, 0.0])
+}
+
+/// hermite segment between values a and b,
+/// tangents m0, m1 already scaled to segment width
+#[inline(always)]
+fn hermite_coefficients(a: f32, b: f32, m0: f32, m1: f32) -> [f32; 4] {
+    [
+        a,
+        m0,
+        (3.0 * (b - a)) - (2.0 * m0) - m1,
+        (2.0 * (a - b)) + m0 + m1,
+    ]
+}
+
+/// cubic bezier with control values p0..p3, as polynomial of parameter u
+#[inline(always)]
+fn bezier_coefficients(p0: f32, p1: f32, p2: f32, p3: f32) -> [f32; 4] {
+    [
+        p0,
+        3.0 * (p1 - p0),
+        3.0 * (p0 - (2.0 * p1) + p2),
+        p3 - (3.0 * p2) + (3.0 * p1) - p0,
+    ]
+}
+
+/// c0 + c1*t + c2*t^2 + c3*t^3 (horner)
+#[inline(always)]
+fn polynomial(c: &[f32; 4], t: f32) -> f32 {
+    ((c[3] * t + c[2]) * t + c[1]) * t + c[0]
+}
+
+/// cubic bezier with control values p0..p3 at parameter u
+#[inline(always)]
+fn bezier(u: f32, p0: f32, p1: f32, p2: f32, p3: f32) -> f32 {
+    let v = 1.0 - u;
+    (v * v * v * p0) + (3.0 * v * v * u * p1) + (3.0 * v * u * u * p2) + (u * u * u * p3)
+}
+
+/// derivative of `bezier` by u
+#[inline(always)]
+fn bezier_derivative(u: f32, p0: f32, p1: f32, p2: f32, p3: f32) -> f32 {
+    let v = 1.0 - u;
+    (3.0 * v * v * (p1 - p0)) + (6.0 * v * u * (p2 - p1)) + (3.0 * u * u * (p3 - p2))
+}
+
+/// bezier parameter u, where X of segment (control x: 0, x1, x2, 1) is equal to x.
+/// X is monotonic (x1, x2 in 0..1), so Newton is tried first and
+/// bisection is fallback for flat derivative (or Newton step out of segment)
+fn solve_bezier(x: f32, x1: f32, x2: f32) -> f32 {
+    const EPSILON: f32 = 1e-6;
+
+    let mut u = x;
+    for _ in 0..8 {
+        let err = bezier(u, 0.0, x1, x2, 1.0) - x;
+        if err.abs() < EPSILON {
+            return u;
+        }
+
+        let d = bezier_derivative(u, 0.0, x1, x2, 1.0);
+        if d.abs() < EPSILON {
+            break;
+        }
+
+        u -= err / d;
+        if !(0.0..=1.0).contains(&u) {
+            break;
+        }
+    }
+
+    let mut low = 0.0;
+    let mut high = 1.0;
+    u = x;
+    for _ in 0..32 {
+        let value = bezier(u, 0.0, x1, x2, 1.0);
+        if (value - x).abs() < EPSILON {
+            break;
+        }
+
+        match value < x {
+            true => low = u,
+            false => high = u,
+        }
+        u = (low + high) * 0.5;
+    }
+
+    u
+}
+
+/// catmull-rom tangent (slope) at point k, from previous to next point.
+/// First/last points are extended with flat virtual neighbor (0.01 away)
+#[inline(always)]
+fn catmull_rom_tangent(points: &[Point], k: usize) -> f32 {
+    let last_ind = points.len() - 1;
+
+    let prev = match k == 0 {
+        true => Point::new(points[k].x - 0.01, points[k].y),
+        false => points[k - 1],
+    };
+    let next = match k == last_ind {
+        true => Point::new(points[k].x + 0.01, points[k].y),
+        false => points[k + 1],
+    };
+
+    (next.y - prev.y) / (next.x - prev.x)
+}
+
+/// Fritsch–Carlson tangent (slope) at point k.
+/// Limited to 3x of secant on both sides (alpha, beta <= 3), so curve
+/// never overshoots and keep monotonic between points.
+#[inline(always)]
+fn monotone_tangent(points: &[Point], k: usize) -> f32 {
+    match secants(points, k) {
+        (Some(d0), Some(d1)) => {
+            // local extremum
+            if d0 * d1 <= 0.0 {
+                return 0.0;
+            }
+
+            let m = (d0 + d1) * 0.5;
+            m.signum() * m.abs().min(3.0 * d0.abs()).min(3.0 * d1.abs())
+        }
+        (Some(d), None) | (None, Some(d)) => d,
+        (None, None) => 0.0,
+    }
+}
+
+/// slopes of segments (left, right) around point k
+#[inline(always)]
+fn secants(points: &[Point], k: usize) -> (Option<f32>, Option<f32>) {
+    let secant = |a: Point, b: Point| (b.y - a.y) / (b.x - a.x);
+
+    let left = match k > 0 {
+        true => Some(secant(points[k - 1], points[k])),
+        false => None,
+    };
+    let right = match k < points.len() - 1 {
+        true => Some(secant(points[k], points[k + 1])),
+        false => None,
+    };
+
+    (left, right)
+}
@@ -0,0 +1,566 @@
+//! CSS easing and SVG path data. SVG is drawn in square of `SVG_SIZE` units (y axis down),
+//! imported paths are sampled and fitted back to catmull-rom points.
+
+use crate::spline::{sample, segment, Segment};
+use crate::{Curve, Interpolation, Point};
+
+/// exported svg is square `0 0 SVG_SIZE SVG_SIZE`
+const SVG_SIZE: f32 = 100.0;
+/// samples of every curve command of imported path
+const SVG_CURVE_SAMPLES: usize = 16;
+/// max deviation of fitted points from imported path (normalized units)
+const FIT_TOLERANCE: f32 = 0.002;
+const FIT_MAX_POINTS: usize = 64;
+
+/// `cubic-bezier(x1, y1, x2, y2)` of single segment curve (`steps(1, jump-end)` for constant).
+/// CSS easing is progress from first to last value, so y is normalized between them
+pub fn encode_css(curve: &Curve) -> Result<String, String> {
+    let points = curve.points();
+    if points.len() != 2 {
+        return Err(format!(
+            "css easing is single segment, curve has {} points",
+            points.len()
+        ));
+    }
+
+    let (from, to) = (points[0].y, points[1].y);
+    if from == to {
+        return Err(String::from("css easing of flat curve is not defined"));
+    }
+
+    // controls x is normalized to segment already
+    let controls = match segment(points, 0, curve.interpolation()).controls() {
+        Some(controls) => controls,
+        None => return Ok(String::from("steps(1, jump-end)")),
+    };
+    let y = |y: f32| (y - from) / (to - from);
+
+    Ok(format!(
+        "cubic-bezier({}, {}, {}, {})",
+        decimal(controls[1].0),
+        decimal(y(controls[1].1)),
+        decimal(controls[2].0),
+        decimal(y(controls[2].1)),
+    ))
+}
+
+/// svg document with curve path (every segment is `C` command, steps are `H`/`V` lines)
+pub fn encode_svg(curve: &Curve) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {size} {size}\" width=\"{size}\" height=\"{size}\">
+  <path d=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.5\"/>
+</svg>
+",
+        encode_svg_path(curve),
+        size = SVG_SIZE
+    )
+}
+
+/// `d` attribute of curve path
+pub fn encode_svg_path(curve: &Curve) -> String {
+    let points = curve.points();
+    let point = |x: f32, y: f32| {
+        format!(
+            "{} {}",
+            decimal(x * SVG_SIZE),
+            decimal((1.0 - y) * SVG_SIZE)
+        )
+    };
+
+    let first = match points.first() {
+        Some(p) => p,
+        None => return String::new(),
+    };
+
+    let mut commands = vec![format!("M {}", point(first.x, first.y))];
+    for ind in 0..points.len() - 1 {
+        let (cp0, cp1) = (points[ind], points[ind + 1]);
+        let width = cp1.x - cp0.x;
+
+        commands.push(match segment(points, ind, curve.interpolation()) {
+            Segment::Constant(_) => format!(
+                "H {} V {}",
+                decimal(cp1.x * SVG_SIZE),
+                decimal((1.0 - cp1.y) * SVG_SIZE)
+            ),
+            s => {
+                let controls = s.controls().unwrap();
+                let controls: Vec<String> = controls[1..]
+                    .iter()
+                    .map(|(x, y)| point(cp0.x + x * width, *y))
+                    .collect();
+                format!("C {}", controls.join(", "))
+            }
+        });
+    }
+
+    commands.join(" ")
+}
+
+/// Svg document (`d` of first `<path>`, bounds from `viewBox`), or only path data (bounds of path).
+/// Path is sampled, then points are added until catmull-rom curve is close enough to samples
+pub fn decode_svg(data: &str) -> Result<Curve, String> {
+    let data = data.trim();
+
+    let (d, bounds) = match data.starts_with('<') {
+        true => {
+            let path = match data.find("<path") {
+                Some(start) => &data[start..],
+                None => return Err(String::from("svg has no <path>")),
+            };
+            let d = match attribute(path, "d") {
+                Some(d) => d,
+                None => return Err(String::from("svg <path> has no d attribute")),
+            };
+            let bounds = match attribute(data, "viewBox") {
+                Some(view_box) => Some(view_box_bounds(view_box)?),
+                None => None,
+            };
+            (d, bounds)
+        }
+        false => (data, None),
+    };
+
+    let mut samples = flatten(d)?;
+    if samples.len() < 2 {
+        return Err(String::from("svg path is empty"));
+    }
+
+    // drawn from right to left
+    if samples[samples.len() - 1].0 < samples[0].0 {
+        samples.reverse();
+    }
+
+    let (x_min, y_min, width, height) = match bounds {
+        Some(bounds) => bounds,
+        None => {
+            let x_min = samples.iter().map(|s| s.0).fold(f32::MAX, f32::min);
+            let x_max = samples.iter().map(|s| s.0).fold(f32::MIN, f32::max);
+            let y_min = samples.iter().map(|s| s.1).fold(f32::MAX, f32::min);
+            let y_max = samples.iter().map(|s| s.1).fold(f32::MIN, f32::max);
+            (x_min, y_min, x_max - x_min, y_max - y_min)
+        }
+    };
+    if width <= 0.0 {
+        return Err(String::from("svg path has no width"));
+    }
+    // flat line is at bottom
+    let height = match height > 0.0 {
+        true => height,
+        false => 1.0,
+    };
+
+    // curve is function of x, backward parts of path are skipped
+    let mut normalized: Vec<Point> = vec![];
+    for (x, y) in samples {
+        let x = ((x - x_min) / width).clamp(0.0, 1.0);
+        let y = (1.0 - (y - y_min) / height).clamp(0.0, 1.0);
+
+        if normalized.last().map_or(true, |last| x > last.x) {
+            normalized.push(Point::new(x, y));
+        }
+    }
+
+    Ok(Curve::new(fit(&normalized)).with_interpolation(Interpolation::CatmullRom))
+}
+
+/// samples subset (first and last included), with catmull-rom curve near all samples
+fn fit(samples: &[Point]) -> Vec<Point> {
+    if samples.len() <= 2 {
+        return samples.to_vec();
+    }
+
+    let mut chosen = vec![0, samples.len() - 1];
+    loop {
+        let points: Vec<Point> = chosen.iter().map(|ind| samples[*ind]).collect();
+
+        let (worst, error) = samples
+            .iter()
+            .enumerate()
+            .map(|(ind, s)| {
+                (
+                    ind,
+                    (sample(&points, s.x, Interpolation::CatmullRom) - s.y).abs(),
+                )
+            })
+            .fold((0, 0.0), |a, b| if b.1 > a.1 { b } else { a });
+
+        if error <= FIT_TOLERANCE || chosen.len() >= FIT_MAX_POINTS {
+            return points;
+        }
+
+        let at = chosen.partition_point(|ind| *ind < worst);
+        chosen.insert(at, worst);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Command(char),
+    Number(f32),
+}
+
+/// commands and numbers of path data (`M0,0L.5.5` is `M 0 0 L 0.5 0.5`)
+fn tokens(d: &str) -> Result<Vec<Token>, String> {
+    let mut result = vec![];
+    let chars: Vec<char> = d.chars().collect();
+
+    let mut ind = 0;
+    while ind < chars.len() {
+        let c = chars[ind];
+        if c.is_whitespace() || c == ',' {
+            ind += 1;
+            continue;
+        }
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            result.push(Token::Command(c));
+            ind += 1;
+            continue;
+        }
+
+        // number: sign, digits with one dot, exponent
+        let start = ind;
+        let mut dot = false;
+        if c == '-' || c == '+' {
+            ind += 1;
+        }
+        while ind < chars.len() {
+            match chars[ind] {
+                '0'..='9' => {}
+                '.' if !dot => dot = true,
+                'e' | 'E' => {
+                    ind += 1;
+                    if ind < chars.len() && (chars[ind] == '-' || chars[ind] == '+') {
+                        ind += 1;
+                    }
+                    continue;
+                }
+                _ => break,
+            }
+            ind += 1;
+        }
+
+        let number: String = chars[start..ind].iter().collect();
+        match number.parse::<f32>() {
+            Ok(v) if v.is_finite() => result.push(Token::Number(v)),
+            _ => return Err(format!("unexpected path data {} at {}", number, start)),
+        }
+        ind = ind.max(start + 1);
+    }
+
+    Ok(result)
+}
+
+/// absolute polyline of path, curves are sampled
+fn flatten(d: &str) -> Result<Vec<(f32, f32)>, String> {
+    let tokens = tokens(d)?;
+    let mut result: Vec<(f32, f32)> = vec![];
+
+    let mut cur = (0.0, 0.0);
+    let mut start = (0.0, 0.0);
+    // reflected control point of S and T commands
+    let mut last_control: Option<(char, (f32, f32))> = None;
+
+    let mut ind = 0;
+    let mut command = ' ';
+    while ind < tokens.len() {
+        if let Token::Command(c) = tokens[ind] {
+            command = c;
+            ind += 1;
+
+            if c == 'Z' || c == 'z' {
+                cur = start;
+                result.push(cur);
+                continue;
+            }
+        }
+
+        let count = match command.to_ascii_uppercase() {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            'A' => return Err(String::from("svg arcs are not supported")),
+            ' ' => return Err(String::from("path data should start with command")),
+            c => return Err(format!("unknown path command {}", c)),
+        };
+
+        let mut values = [0.0; 6];
+        for value in values.iter_mut().take(count) {
+            match tokens.get(ind) {
+                Some(Token::Number(v)) => *value = *v,
+                _ => {
+                    return Err(format!(
+                        "path command {} expects {} numbers",
+                        command, count
+                    ))
+                }
+            }
+            ind += 1;
+        }
+
+        let relative = command.is_ascii_lowercase();
+        let abs = |x: f32, y: f32| match relative {
+            true => (cur.0 + x, cur.1 + y),
+            false => (x, y),
+        };
+        let reflected = |kind: char| match last_control {
+            Some((k, c)) if k == kind => (2.0 * cur.0 - c.0, 2.0 * cur.1 - c.1),
+            _ => cur,
+        };
+
+        let upper = command.to_ascii_uppercase();
+        let mut control = None;
+        match upper {
+            'M' => {
+                cur = abs(values[0], values[1]);
+                start = cur;
+                result.push(cur);
+                // next pairs are lines
+                command = if relative { 'l' } else { 'L' };
+            }
+            'L' => {
+                cur = abs(values[0], values[1]);
+                result.push(cur);
+            }
+            'H' => {
+                cur.0 = if relative {
+                    cur.0 + values[0]
+                } else {
+                    values[0]
+                };
+                result.push(cur);
+            }
+            'V' => {
+                cur.1 = if relative {
+                    cur.1 + values[0]
+                } else {
+                    values[0]
+                };
+                result.push(cur);
+            }
+            'C' | 'S' => {
+                let (c1, c2, to) = match upper {
+                    'C' => (
+                        abs(values[0], values[1]),
+                        abs(values[2], values[3]),
+                        abs(values[4], values[5]),
+                    ),
+                    _ => (
+                        reflected('C'),
+                        abs(values[0], values[1]),
+                        abs(values[2], values[3]),
+                    ),
+                };
+                cubic(&mut result, cur, c1, c2, to);
+                control = Some(('C', c2));
+                cur = to;
+            }
+            _ => {
+                let (c, to) = match upper {
+                    'Q' => (abs(values[0], values[1]), abs(values[2], values[3])),
+                    _ => (reflected('Q'), abs(values[0], values[1])),
+                };
+                // quadratic is cubic with handles at 2/3
+                let c1 = (
+                    cur.0 + (c.0 - cur.0) * 2.0 / 3.0,
+                    cur.1 + (c.1 - cur.1) * 2.0 / 3.0,
+                );
+                let c2 = (
+                    to.0 + (c.0 - to.0) * 2.0 / 3.0,
+                    to.1 + (c.1 - to.1) * 2.0 / 3.0,
+                );
+                cubic(&mut result, cur, c1, c2, to);
+                control = Some(('Q', c));
+                cur = to;
+            }
+        }
+        last_control = control;
+    }
+
+    Ok(result)
+}
+
+fn cubic(
+    result: &mut Vec<(f32, f32)>,
+    p0: (f32, f32),
+    p1: (f32, f32),
+    p2: (f32, f32),
+    p3: (f32, f32),
+) {
+    for n in 1..=SVG_CURVE_SAMPLES {
+        let u = n as f32 / SVG_CURVE_SAMPLES as f32;
+        let v = 1.0 - u;
+        let (a, b, c, d) = (v * v * v, 3.0 * v * v * u, 3.0 * v * u * u, u * u * u);
+
+        result.push((
+            a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+            a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+        ));
+    }
+}
+
+/// value of `name="value"` attribute (first one)
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(" {}=", name);
+    let start = tag.find(&pattern)? + pattern.len();
+    let quote = tag[start..].chars().next()?;
+    let value = &tag[start + 1..];
+
+    value.find(quote).map(|end| &value[..end])
+}
+
+/// (x, y, width, height)
+fn view_box_bounds(view_box: &str) -> Result<(f32, f32, f32, f32), String> {
+    let values: Vec<f32> = view_box
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|err| format!("viewBox is not numbers: {}", err))?;
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(String::from("viewBox should be finite numbers"));
+    }
+
+    match values[..] {
+        [x, y, w, h] => Ok((x, y, w, h)),
+        _ => Err(format!("viewBox expects 4 numbers, got {}", values.len())),
+    }
+}
+
+/// up to 3 decimals, without trailing zeros
+fn decimal(v: f32) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" | "" => String::from("0"),
+        s => String::from(s),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Tangent;
+
+    #[test]
+    pub fn css_test() {
+        let linear = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)])
+            .with_interpolation(Interpolation::Linear);
+        assert_eq!(
+            encode_css(&linear).unwrap(),
+            "cubic-bezier(0.333, 0.333, 0.667, 0.667)"
+        );
+
+        // css `ease`, from weighted handles
+        let ease = Curve::new(vec![
+            Point::new(0.0, 0.0).with_tangent(Tangent::free(0.0, 0.4).with_weights(0.0, 0.25)),
+            Point::new(1.0, 1.0).with_tangent(Tangent::free(0.0, 0.0).with_weights(0.75, 0.0)),
+        ])
+        .with_interpolation(Interpolation::Bezier);
+        assert_eq!(
+            encode_css(&ease).unwrap(),
+            "cubic-bezier(0.25, 0.1, 0.25, 1)"
+        );
+
+        // progress from first to last value
+        let falling = Curve::new(vec![Point::new(0.0, 1.0), Point::new(1.0, 0.5)])
+            .with_interpolation(Interpolation::Linear);
+        assert_eq!(encode_css(&falling).unwrap(), encode_css(&linear).unwrap());
+
+        let step = linear.clone().with_interpolation(Interpolation::Constant);
+        assert_eq!(encode_css(&step).unwrap(), "steps(1, jump-end)");
+
+        let three = Curve::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 0.0),
+        ]);
+        assert_eq!(
+            encode_css(&three).unwrap_err(),
+            "css easing is single segment, curve has 3 points"
+        );
+    }
+
+    #[test]
+    pub fn svg_encode_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.5, 1.0),
+            Point::new(1.0, 0.5),
+        ])
+        .with_interpolation(Interpolation::Linear);
+        assert_eq!(
+            encode_svg_path(&curve),
+            "M 0 100 C 16.667 66.667, 33.333 33.333, 50 0 C 66.667 16.667, 83.333 33.333, 100 50"
+        );
+
+        let step = curve.with_interpolation(Interpolation::Constant);
+        assert_eq!(encode_svg_path(&step), "M 0 100 H 50 V 0 H 100 V 50");
+        assert!(encode_svg(&step).contains("<path d=\"M 0 100 H 50 V 0 H 100 V 50\""));
+    }
+
+    #[test]
+    pub fn svg_roundtrip_test() {
+        let curve = Curve::new(vec![
+            Point::new(0.0, 0.5),
+            Point::new(0.2, 0.3),
+            Point::new(0.4, 0.5),
+            Point::new(0.8, 0.9),
+            Point::new(0.85, 0.05),
+            Point::new(1.0, 0.5),
+        ]);
+
+        let decoded = decode_svg(&encode_svg(&curve)).unwrap();
+        assert!(decoded.points().len() < 64);
+        assert_eq!(decoded.points()[0].x, 0.0);
+        assert_eq!(decoded.points().last().unwrap().x, 1.0);
+
+        for n in 0..=100 {
+            let x = n as f32 / 100.0;
+            let error = (decoded.sample(x) - curve.sample(x)).abs();
+            assert!(error < 0.01, "x={} error={}", x, error);
+        }
+    }
+
+    #[test]
+    pub fn svg_path_test() {
+        // relative commands, implicit lines after move, numbers without separators
+        assert_eq!(
+            flatten("m0,10 10-10h10v10l-.5.5z").unwrap(),
+            vec![
+                (0.0, 10.0),
+                (10.0, 0.0),
+                (20.0, 0.0),
+                (20.0, 10.0),
+                (19.5, 10.5),
+                (0.0, 10.0)
+            ]
+        );
+
+        // bounds of path, y axis is flipped
+        let curve = decode_svg("M 10 20 L 30 0").unwrap();
+        assert_eq!(
+            curve.points(),
+            &[Point::new(0.0, 0.0), Point::new(1.0, 1.0)]
+        );
+
+        // quadratic is sampled as cubic
+        let quad = flatten("M0 0 Q 5 10 10 0").unwrap();
+        assert_eq!(quad.len(), 1 + SVG_CURVE_SAMPLES);
+        assert_eq!(quad[SVG_CURVE_SAMPLES / 2], (5.0, 5.0));
+
+        assert_eq!(
+            decode_svg("M 0 0 A 5 5 0 0 1 10 10").unwrap_err(),
+            "svg arcs are not supported"
+        );
+        assert_eq!(
+            decode_svg("M 0 0 C 1 1").unwrap_err(),
+            "path command C expects 6 numbers"
+        );
+        assert_eq!(
+            decode_svg("<svg viewBox=\"0 0 1\"><path d=\"M0 0 L1 1\"/></svg>").unwrap_err(),
+            "viewBox expects 4 numbers, got 3"
+        );
+    }
+}
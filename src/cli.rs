@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+
+use std::path::Path;
+
+use spline_curve::{codegen, codegen_lut, encode_css, Curve, Format, Language, Point};
+
+use crate::consts::*;
+use crate::render::render;
+use crate::settings::Settings;
+
+const USAGE: &str = "usage:
+  rs-spline-editor [file.curve]                          open editor window (with attached file)
+  rs-spline-editor validate <file.curve>                 check file format and points
+  rs-spline-editor sample <file.curve> [--steps N] [-o out.curve]
+                                                         sample N evenly spaced points (default 256)
+  rs-spline-editor convert <file> --to <curve|json|ron|csv|unity|godot|svg> [-o out]
+  rs-spline-editor css <file.curve>                      css easing of single segment curve
+  rs-spline-editor render <file.curve> -o <out.png> [--size WxH]
+  rs-spline-editor bake <file.curve> [--size N] [--format <bin|text>] [-o out.lut]
+                                                         lookup table of N values (default 256),
+                                                         bin is raw f32 little-endian (needs -o)
+  rs-spline-editor codegen <file.curve> [--lang <rust|glsl|wgsl>] [--name NAME] [--lut N] [-o out]
+                                                         function with curve (or baked lut of N values)";
+
+const DEFAULT_STEPS: usize = 256;
+
+/// Headless mode, executed without starting bevy App.
+/// Returns exit code, or None when args is not a known subcommand (editor should start)
+pub fn run(args: &[String]) -> Option<i32> {
+    let cmd = args.first()?;
+    let args = &args[1..];
+
+    let result = match cmd.as_str() {
+        "validate" => validate(args),
+        "sample" => sample(args),
+        "convert" => convert(args),
+        "css" => css(args),
+        "render" => render_png(args),
+        "bake" => bake(args),
+        "codegen" => generate(args),
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ if cmd.starts_with('-') => Err(format!("unknown option {}\n{}", cmd, USAGE)),
+        _ => return None,
+    };
+
+    match result {
+        Ok(_) => Some(0),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            Some(1)
+        }
+    }
+}
+
+struct Args {
+    file: String,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    /// all options (`--steps`, `-o`, ..) expect value
+    fn parse(args: &[String], known: &[&str]) -> Result<Self, String> {
+        let mut file: Option<String> = None;
+        let mut options = HashMap::new();
+
+        let mut it = args.iter();
+        while let Some(arg) = it.next() {
+            if !arg.starts_with('-') {
+                if file.is_some() {
+                    return Err(format!("unexpected argument {}\n{}", arg, USAGE));
+                }
+
+                file = Some(arg.clone());
+                continue;
+            }
+
+            if !known.contains(&arg.as_str()) {
+                return Err(format!("unknown option {}\n{}", arg, USAGE));
+            }
+
+            match it.next() {
+                Some(value) => options.insert(arg.clone(), value.clone()),
+                None => return Err(format!("option {} expects value", arg)),
+            };
+        }
+
+        match file {
+            Some(file) => Ok(Self { file, options }),
+            None => Err(format!("curve file is not specified\n{}", USAGE)),
+        }
+    }
+
+    fn option(&self, name: &str) -> Option<&String> {
+        self.options.get(name)
+    }
+
+    /// first curve of document
+    fn curve(&self) -> Result<Curve, String> {
+        Ok(self.curves()?.swap_remove(0))
+    }
+
+    fn curves(&self) -> Result<Vec<Curve>, String> {
+        let data = std::fs::read_to_string(&self.file)
+            .map_err(|err| format!("can`t open: {} ({})", err, self.file))?;
+
+        let format = Format::from_path(&self.file).unwrap_or(Format::detect(&data));
+        format
+            .decode_all(&data)
+            .map_err(|err| format!("invalid format: {} ({})", err, self.file))
+    }
+
+    /// write to `-o` file, or stdout
+    fn output(&self, content: &[u8]) -> Result<(), String> {
+        match self.option("-o") {
+            Some(path) => std::fs::write(path, content)
+                .map_err(|err| format!("failed save: {} ({})", err, path)),
+            None => {
+                print!("{}", String::from_utf8_lossy(content));
+                Ok(())
+            }
+        }
+    }
+}
+
+fn validate(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let curves = args.curves()?;
+
+    for (ind, curve) in curves.iter().enumerate() {
+        curve
+            .validate()
+            .map_err(|err| format!("curve {}: {} ({})", ind + 1, err, args.file))?;
+    }
+
+    let points: usize = curves.iter().map(|c| c.points().len()).sum();
+    match curves.len() {
+        1 => println!("{}: ok, {} points", args.file, points),
+        n => println!("{}: ok, {} curves, {} points", args.file, n, points),
+    }
+    Ok(())
+}
+
+fn sample(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--steps", "-o"])?;
+    let curve = args.curve()?;
+
+    let steps = match args.option("--steps") {
+        Some(steps) => steps
+            .parse::<usize>()
+            .map_err(|err| format!("--steps is not number: {}", err))?,
+        None => DEFAULT_STEPS,
+    };
+
+    if steps < 2 {
+        return Err(String::from("--steps should be at least 2"));
+    }
+
+    let mut samples: Vec<Point> = vec![];
+    for n in 0..steps {
+        let x = n as f32 / (steps - 1) as f32;
+        samples.push(Point::new(x, curve.sample(x)));
+    }
+
+    args.output(Curve::new(samples).encode().as_bytes())
+}
+
+fn convert(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--to", "-o"])?;
+    let curves = args.curves()?;
+
+    let format = match args.option("--to") {
+        Some(format) => format.parse::<Format>()?,
+        None => return Err(String::from("--to is not specified")),
+    };
+
+    // other formats have single curve, extra curves are not dropped silently
+    format.check_document(&curves)?;
+    args.output(format.encode_all(&curves).as_bytes())
+}
+
+fn css(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[])?;
+    let curve = args.curve()?;
+
+    println!("{}", encode_css(&curve)?);
+    Ok(())
+}
+
+fn render_png(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["-o", "--size"])?;
+    let curves = args.curves()?;
+
+    if args.option("-o").is_none() {
+        return Err(String::from("-o is not specified"));
+    }
+
+    let (width, height) = match args.option("--size") {
+        Some(size) => parse_size(size)?,
+        None => (
+            (PLOT_WIDTH * WINDOW_SCALE) as u32,
+            (PLOT_HEIGHT * WINDOW_SCALE) as u32,
+        ),
+    };
+
+    // first curve is active, as in editor
+    let png = render(&curves, 0, &Settings::default(), width, height).encode_png()?;
+    args.output(&png)
+}
+
+fn bake(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--size", "--format", "-o"])?;
+    let curve = args.curve()?;
+
+    let size = match args.option("--size") {
+        Some(size) => size
+            .parse::<usize>()
+            .map_err(|err| format!("--size is not number: {}", err))?,
+        None => LUT_SIZE,
+    };
+
+    if size < 2 {
+        return Err(String::from("--size should be at least 2"));
+    }
+
+    let lut = curve.bake(size);
+    let content = match args.option("--format").map(|f| f.as_str()) {
+        Some("bin") | None => {
+            if args.option("-o").is_none() {
+                return Err(String::from("-o is not specified (or use --format text)"));
+            }
+            lut.to_bytes()
+        }
+        Some("text") => lut.to_text().into_bytes(),
+        Some(format) => return Err(format!("unknown format {}, expected bin|text", format)),
+    };
+
+    // stdout can be used for lut listing
+    eprintln!("max error: {:.6}", lut.max_error(&curve));
+    args.output(&content)
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["--lang", "--name", "--lut", "-o"])?;
+    let curve = args.curve()?;
+
+    let language = match args.option("--lang") {
+        Some(lang) => lang.parse::<Language>()?,
+        None => Language::default(),
+    };
+
+    // function name from file name by default
+    let name = match args.option("--name") {
+        Some(name) => name.clone(),
+        None => Path::new(&args.file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    let content = match args.option("--lut") {
+        Some(size) => {
+            let size = size
+                .parse::<usize>()
+                .map_err(|err| format!("--lut is not number: {}", err))?;
+            if size < 2 {
+                return Err(String::from("--lut should be at least 2"));
+            }
+
+            codegen_lut(&curve.bake(size), &name, language)
+        }
+        None => codegen(&curve, &name, language).map_err(|err| format!("{} (use --lut N)", err))?,
+    };
+
+    args.output(content.as_bytes())
+}
+
+fn parse_size(size: &str) -> Result<(u32, u32), String> {
+    let err = || format!("--size expects WxH (like 960x480), got {}", size);
+
+    let (w, h) = size.split_once('x').ok_or_else(err)?;
+    let w = w.parse::<u32>().map_err(|_| err())?;
+    let h = h.parse::<u32>().map_err(|_| err())?;
+
+    if w == 0 || h == 0 {
+        return Err(err());
+    }
+    if w > MAX_RENDER_SIZE || h > MAX_RENDER_SIZE {
+        return Err(format!(
+            "--size should be at most {}x{}, got {}",
+            MAX_RENDER_SIZE, MAX_RENDER_SIZE, size
+        ));
+    }
+
+    Ok((w, h))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    pub fn args_test() {
+        let args = Args::parse(&strings(&["a.curve", "--steps", "8"]), &["--steps"]).unwrap();
+        assert_eq!(args.file, "a.curve");
+        assert_eq!(args.option("--steps").unwrap(), "8");
+        assert!(args.option("-o").is_none());
+
+        // option before file
+        let args = Args::parse(&strings(&["-o", "b.curve", "a.curve"]), &["-o"]).unwrap();
+        assert_eq!(args.file, "a.curve");
+        assert_eq!(args.option("-o").unwrap(), "b.curve");
+
+        let err = |args: &[&str]| Args::parse(&strings(args), &["-o"]).err().unwrap();
+        assert!(err(&["a.curve", "--to", "json"]).starts_with("unknown option --to"));
+        assert_eq!(err(&["a.curve", "-o"]), "option -o expects value");
+        assert!(err(&["a.curve", "b.curve"]).starts_with("unexpected argument b.curve"));
+        assert!(err(&[]).starts_with("curve file is not specified"));
+    }
+
+    #[test]
+    pub fn parse_size_test() {
+        assert_eq!(parse_size("960x480").unwrap(), (960, 480));
+        assert!(parse_size("960").is_err());
+        assert!(parse_size("960x").is_err());
+        assert!(parse_size("0x480").is_err());
+        assert!(parse_size("-1x480").is_err());
+
+        // pixel buffer size of 65536x65536 overflows u32
+        assert!(parse_size("8192x8192").is_ok());
+        let err = parse_size("65536x65536").unwrap_err();
+        assert!(err.starts_with("--size should be at most 8192x8192"));
+    }
+
+    #[test]
+    pub fn errors_test() {
+        let missing = validate(&strings(&["missing.curve"])).unwrap_err();
+        assert!(missing.starts_with("can`t open"));
+
+        let file = "examples/simple.curve";
+        assert!(convert(&strings(&[file, "--to", "xml"])).is_err());
+        assert_eq!(
+            convert(&strings(&[file])).unwrap_err(),
+            "--to is not specified"
+        );
+
+        let steps = sample(&strings(&[file, "--steps", "many"])).unwrap_err();
+        assert!(steps.starts_with("--steps is not number"));
+        assert_eq!(
+            sample(&strings(&[file, "--steps", "1"])).unwrap_err(),
+            "--steps should be at least 2"
+        );
+
+        assert_eq!(
+            render_png(&strings(&[file])).unwrap_err(),
+            "-o is not specified"
+        );
+
+        // editor is started for file argument, options are errors
+        assert_eq!(run(&strings(&[file])), None);
+        assert_eq!(run(&strings(&[])), None);
+        assert_eq!(run(&strings(&["--verbose"])), Some(1));
+    }
+}
@@ -1,34 +1,74 @@
 use bevy::prelude::Vec2;
 
+use crate::resources::{Layout, View};
+
+/// plot size of initial window and rendered png (window pixels are `WINDOW_SCALE` times bigger)
 pub const PLOT_WIDTH: f32 = 480.0;
 pub const PLOT_HEIGHT: f32 = 240.0;
 pub const STATUS_BAR_HEIGHT: f32 = 32.0;
 
 pub const WINDOW_SCALE: f32 = 2.0;
+/// initial window size with default `window_scale` (it can be resized, see `Layout`)
 pub const WINDOW_WIDTH: f32 = PLOT_WIDTH * WINDOW_SCALE;
 pub const WINDOW_HEIGHT: f32 = PLOT_HEIGHT * WINDOW_SCALE + STATUS_BAR_HEIGHT;
+pub const MIN_WINDOW_WIDTH: f32 = 480.0;
+pub const MIN_WINDOW_HEIGHT: f32 = 240.0;
 pub const OFFSET: f32 = 10.0;
 
+/// scale, radius, grid lines, zoom, nudge, precision and hues are defaults of `Settings`
 pub const ACTIVE_RADIUS: f32 = 0.03;
+pub const HANDLE_LENGTH: f32 = 0.08;
 
-#[inline(always)]
-pub fn screen(v: Vec2) -> Vec2 {
-    return Vec2::new(
-        (v.x * WINDOW_SCALE) - (WINDOW_WIDTH / 2.0),
-        (v.y * -WINDOW_SCALE) + (WINDOW_HEIGHT / 2.0),
-    );
-}
+/// max (x, y) grid steps in view, step is round value of real units
+pub const GRID_LINES: (usize, usize) = (5, 4);
+/// max grid steps of fixed snap step in view, denser grid shows every n-th line
+pub const MAX_GRID_LINES: usize = 10;
+/// label entities spawned per axis (grid lines with edges)
+pub const LABELS_PER_AXIS: usize = 12;
+pub const LABEL_FONT_SIZE: f32 = 14.0;
+
+/// smallest visible part of curve (100x zoom)
+pub const MIN_VIEW_SIZE: f32 = 0.01;
+/// mouse wheel zoom per line
+pub const ZOOM_STEP: f32 = 1.2;
+/// "frame all" margin around points, part of framed size
+pub const FRAME_MARGIN: f32 = 0.05;
+
+/// hue of curve by its index in document (repeated for more curves)
+pub const CURVE_HUES: [f32; 6] = [45.0, 200.0, 330.0, 120.0, 270.0, 15.0];
+
+/// Shift+arrows nudge of selected points (normalized), with Ctrl - coarse, with Alt - fine
+pub const NUDGE_STEP: f32 = 0.01;
+pub const NUDGE_STEP_COARSE: f32 = 0.1;
+pub const NUDGE_STEP_FINE: f32 = 0.001;
+
+/// decimals of moving point coords in status bar
+pub const COORDS_PRECISION: usize = 2;
 
+pub const HISTORY_LIMIT: usize = 100;
+
+pub const DEFAULT_FILE_NAME: &str = "untitled.curve";
+
+/// editor preferences in user config directory, checked for changes every few seconds
+pub const SETTINGS_FILE_NAME: &str = "settings.ron";
+pub const SETTINGS_CHECK_INTERVAL: f32 = 1.0;
+
+/// entries in baked lookup table (ctrl+b, cli bake)
+pub const LUT_SIZE: usize = 256;
+
+/// max width and height of rendered png (cli render), keeps pixel buffer size in u32
+pub const MAX_RENDER_SIZE: u32 = 8192;
+
+/// curve coords (normalized) to screen, through visible part of curve and current window size
 #[inline(always)]
-pub fn plot(v_norm: Vec2) -> Vec2 {
-    screen(Vec2 {
-        x: Vec2::new(OFFSET, 0.0)
-            .lerp(Vec2::new(PLOT_WIDTH - OFFSET, 0.0), v_norm.x)
-            .x,
-        y: Vec2::new(0.0, OFFSET)
-            .lerp(Vec2::new(0.0, PLOT_HEIGHT - OFFSET), 1.0 - v_norm.y)
-            .y,
-    })
+pub fn plot(layout: &Layout, view: &View, v: Vec2) -> Vec2 {
+    let (tl, br) = layout.plot_rect();
+    let v_norm = view.project(v);
+
+    layout.screen(Vec2::new(
+        tl.x + (br.x - tl.x) * v_norm.x,
+        tl.y + (br.y - tl.y) * (1.0 - v_norm.y),
+    ))
 }
 
 #[inline(always)]
@@ -1,78 +1,131 @@
 use bevy::{prelude::*, text::Text, time::Time};
 
 use crate::consts::*;
+use crate::keymap::{Action, Keys};
 use crate::resources::*;
-use crate::spline::sample;
-use crate::StatusBarTextMarker;
+use crate::settings::Settings;
+use crate::{AxisLabel, StatusBarTextMarker};
 
-pub fn draw_plot(mut gz: Gizmos, area: Res<Area>) {
-    let offset2d = Vec2::splat(OFFSET);
+pub fn draw_plot(
+    mut gz: Gizmos,
+    area: Res<Area>,
+    view: Res<View>,
+    layout: Res<Layout>,
+    snap: Res<Snap>,
+    settings: Res<Settings>,
+) {
     let point_size = Vec2::splat(5.0);
 
     // draw outline
-    let pxoffset = Vec2::new(0.0, 1.0);
-    let tl = screen(offset2d - pxoffset);
-    let br = screen(
-        Vec2 {
-            x: PLOT_WIDTH,
-            y: PLOT_HEIGHT,
-        } - offset2d
-            + pxoffset,
+    let pxoffset = Vec2::new(0.0, WINDOW_SCALE);
+    let (tl, br) = layout.plot_rect();
+    draw_box(
+        &mut gz,
+        layout.screen(tl - pxoffset),
+        layout.screen(br + pxoffset),
+        Color::GRAY,
     );
-    draw_box(&mut gz, tl, br, Color::GRAY);
+
+    // grid adapts to zoom (or shows snap step), lines on view edges are only labeled
+    let (lines_x, lines_y) = view.grid(area.range(), snap.step, settings.grid_lines);
+    let inside = |at: f32, min: f32, max: f32| at > min + 0.0001 && at < max - 0.0001;
 
     // draw axis Y
-    for axis_y in vec![0.25, 0.5, 0.75] {
-        let left = plot(Vec2::new(0.0, axis_y));
-        let rigth = plot(Vec2::new(1.0, axis_y));
+    for line in lines_y {
+        if !inside(line.at, view.min.y, view.max.y) {
+            continue;
+        }
+
+        let left = plot(&layout, &view, Vec2::new(view.min.x, line.at));
+        let rigth = plot(&layout, &view, Vec2::new(view.max.x, line.at));
 
         gz.line_2d(left, rigth, color_light(0.05));
     }
 
     // draw axis X
-    for axis_x in vec![0.2, 0.4, 0.5, 0.6, 0.8] {
-        let top: Vec2 = plot(Vec2::new(axis_x, 0.0));
-        let bottom = plot(Vec2::new(axis_x, 1.0));
+    for line in lines_x {
+        if !inside(line.at, view.min.x, view.max.x) {
+            continue;
+        }
+
+        let top: Vec2 = plot(&layout, &view, Vec2::new(line.at, view.min.y));
+        let bottom = plot(&layout, &view, Vec2::new(line.at, view.max.y));
 
         gz.line_2d(top, bottom, color_light(0.03));
     }
 
-    // draw linear
-    let mut prev = Vec2::ZERO;
-    let mut points = area.points.clone();
-    points.sort_by(|a, b| a.uncommited.x.partial_cmp(&b.uncommited.x).unwrap());
+    // draw other curves of document (dimmed, not editable)
+    for (ind, curve) in area.layers() {
+        let spline = curve.prepare();
+        let color = color_curve(&settings.curve_hues, ind, 0.25);
 
-    let mut index = -1;
-    for p in points.iter() {
-        let cur = plot(p.uncommited);
+        let values = samples(&view, |x| spline.sample(x));
+        for pair in values.windows(2) {
+            draw_line(&mut gz, &layout, &view, pair[0], pair[1], color, color);
+        }
 
-        index += 1;
-        if index == 0 {
-            prev = cur;
-            continue;
+        for p in curve.points() {
+            draw_point(
+                &mut gz,
+                &layout,
+                &view,
+                Vec2::new(p.x, p.y),
+                point_size * 0.6,
+                color,
+            );
         }
+    }
 
-        gz.line_2d(prev, cur, color_light(0.35));
-        prev = cur;
+    // draw linear
+    let mut points = area.points.clone();
+    points.sort_by(|a, b| a.uncommited.x.partial_cmp(&b.uncommited.x).unwrap());
+
+    for pair in points.windows(2) {
+        let color = color_light(0.35);
+        draw_line(
+            &mut gz,
+            &layout,
+            &view,
+            pair[0].uncommited,
+            pair[1].uncommited,
+            color,
+            color,
+        );
     }
 
-    let mut prev_value: Vec2 = Vec2::ZERO;
-    let spline: Vec<Vec2> = points.clone().iter().map(|p| p.uncommited).collect();
-    let spline: &[Vec2] = &spline;
+    let spline = area.preview_curve().prepare();
+    // gradient by x for single curve, own color when curves should be distinguished
+    let layered = area.curves.len() > 1;
 
-    for n in 0..=100 {
-        let x = (n as f32) * 0.01;
-        let value = sample(spline, x);
+    let values = samples(&view, |x| spline.sample(x));
+    for pair in values.windows(2) {
+        let (from, to) = match layered {
+            true => {
+                let color = color_curve(&settings.curve_hues, area.active, 0.5);
+                (color, color)
+            }
+            false => (color_axis_x(pair[0].x), color_axis_x(pair[1].x)),
+        };
 
-        let clamped = value.clamp(0.0, 1.0);
-        let value = Vec2::new(x, clamped);
-        gz.line_gradient_2d(
-            plot(prev_value),
-            plot(value),
-            color_axis_x(prev_value.x),
-            color_axis_x(value.x),
-        );
-        prev_value = value;
+        draw_line(&mut gz, &layout, &view, pair[0], pair[1], from, to);
+    }
+
+    // draw tangent handles
+    if let (Some(p), Some((handle_in, handle_out))) = (area.focused_point(), area.handles(&view)) {
+        let handle_size = point_size * 0.6;
+
+        for handle in [handle_in, handle_out] {
+            let color = color_light(0.6);
+            draw_line(&mut gz, &layout, &view, p.uncommited, handle, color, color);
+            draw_point(
+                &mut gz,
+                &layout,
+                &view,
+                handle,
+                handle_size,
+                settings.handle_color,
+            );
+        }
     }
 
     // draw points
@@ -82,28 +135,85 @@ pub fn draw_plot(mut gz: Gizmos, area: Res<Area>) {
             true => p.uncommited,
         };
 
-        let cur = plot(norm);
-        let mut color = Color::GOLD;
+        let mut color = settings.point_color;
 
+        if area.focused == Some(p.commited) {
+            color = settings.focused_color;
+        }
         if p.selected {
-            color = Color::WHITE;
+            color = settings.selected_color;
         }
 
-        draw_box(&mut gz, cur - point_size, cur + point_size, color);
+        draw_point(&mut gz, &layout, &view, norm, point_size, color);
     }
 }
 
+/// curve values over visible x (clamped to 0..1 like file values)
+fn samples(view: &View, sample: impl Fn(f32) -> f32) -> Vec<Vec2> {
+    (0..=100)
+        .map(|n| {
+            let x = view.min.x + view.size().x * (n as f32) * 0.01;
+            Vec2::new(x, sample(x).clamp(0.0, 1.0))
+        })
+        .collect()
+}
+
+/// line part inside view, gradient colors are for whole line
+fn draw_line(
+    gz: &mut Gizmos,
+    layout: &Layout,
+    view: &View,
+    a: Vec2,
+    b: Vec2,
+    from: Color,
+    to: Color,
+) {
+    let Some((clipped_a, clipped_b)) = view.clip(a, b) else {
+        return;
+    };
+
+    let color_at = |v: Vec2| {
+        let t = match a.distance(b) {
+            d if d > 0.0 => a.distance(v) / d,
+            _ => 0.0,
+        };
+        Color::from(Vec4::from(from).lerp(Vec4::from(to), t))
+    };
+
+    gz.line_gradient_2d(
+        plot(layout, view, clipped_a),
+        plot(layout, view, clipped_b),
+        color_at(clipped_a),
+        color_at(clipped_b),
+    );
+}
+
+fn draw_point(gz: &mut Gizmos, layout: &Layout, view: &View, v: Vec2, size: Vec2, c: Color) {
+    if !view.contains(v) {
+        return;
+    }
+
+    let cur = plot(layout, view, v);
+    draw_box(gz, cur - size, cur + size, c);
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn draw_status_bar(
-    mut text_query: Query<&mut Text, With<StatusBarTextMarker>>,
+    mut text_query: Query<(&mut Text, &mut Transform), With<StatusBarTextMarker>>,
     mut gz: Gizmos,
     mut status_bar: ResMut<StatusBar>,
     time: Res<Time>,
     mouse_plot: Res<MousePlot>,
     file_info: Res<AttachedFile>,
+    prompt: Res<Prompt>,
     area: Res<Area>,
+    layout: Res<Layout>,
+    snap: Res<Snap>,
+    settings: Res<Settings>,
+    keys: Keys,
 ) {
-    let tl = screen(Vec2::new(0.0, PLOT_HEIGHT));
-    let tr = screen(Vec2::new(PLOT_WIDTH, PLOT_HEIGHT));
+    let tl = layout.screen(Vec2::new(0.0, layout.status_bar_top()));
+    let tr = layout.screen(Vec2::new(layout.width, layout.status_bar_top()));
 
     // draw background
     gz.line_2d(tl, tr, Color::DARK_GRAY);
@@ -132,52 +242,183 @@ pub fn draw_status_bar(
         color = important.1;
     }
 
-    // add coords
-    let mut moving_point: Option<Point> = None;
-    for p in area.points.iter() {
-        if p.selected {
-            moving_point = Some(*p);
-            break;
-        }
-    }
+    // add coords (of grabbed point, when selection is moved)
+    let moving_point: Option<Point> = area
+        .drag
+        .and_then(|drag| area.points.iter().find(|p| p.commited == drag.anchor))
+        .copied();
 
+    // real units of curve range
+    let precision = settings.precision;
     let coords_text = match moving_point {
-        Some(p) => format!(
-            "moving=[{:.2},{:.2}]->[{:.2},{:.2}]",
-            p.commited.x, p.commited.y, p.uncommited.x, p.uncommited.y
-        ),
-        None => format!("[{:.1},{:.1}]", mouse_plot.coords.x, mouse_plot.coords.y),
+        Some(p) => {
+            let (from, to) = (area.real(p.commited), area.real(p.uncommited));
+            format!(
+                "moving=[{:.*},{:.*}]->[{:.*},{:.*}]",
+                precision, from.x, precision, from.y, precision, to.x, precision, to.y
+            )
+        }
+        None => {
+            let coords = area.real(mouse_plot.coords);
+            let precision = precision - 1;
+            format!("[{:.*},{:.*}]", precision, coords.x, precision, coords.y)
+        }
     };
 
-    text = format!("{} {}", coords_text, text);
+    let mode_text = match area.focused_point() {
+        Some(p) => format!("[{}, tangent: {}]", area.interpolation, p.tangent.mode),
+        None => format!("[{}]", area.interpolation),
+    };
+
+    text = format!("{} {} {}", coords_text, mode_text, text);
+
+    let grid = snap.grid != keys.pressed(Action::Snap);
+    if let Some(label) = snap.label(grid) {
+        text = format!("{} {}", label, text);
+    }
+
+    let selected = area.selected().len();
+    if selected > 1 {
+        text = format!("[{} selected] {}", selected, text);
+    }
 
-    // change text
-    for mut status_bar_text in &mut text_query {
+    if area.curves.len() > 1 {
+        text = format!(
+            "[{}/{} {}] {}",
+            area.active + 1,
+            area.curves.len(),
+            area.curve_label(),
+            text
+        );
+    }
+
+    // prompt input is more important than everything
+    if prompt.active {
+        text = match prompt.kind {
+            PromptKind::SavePath => {
+                format!("save as: {}_ (enter - save, esc - cancel)", prompt.text)
+            }
+            PromptKind::Range => format!(
+                "range x_min:x_max:y_min:y_max: {}_ (enter - apply, empty - 0..1, esc - cancel)",
+                prompt.text
+            ),
+            PromptKind::Point => {
+                format!("point x:y: {}_ (enter - apply, esc - cancel)", prompt.text)
+            }
+            PromptKind::SnapStep => format!(
+                "snap step x:y: {}_ (enter - apply, empty - by zoom, esc - cancel)",
+                prompt.text
+            ),
+        };
+        color = Color::WHITE;
+    }
+
+    // change text, follow window size
+    if let Some((mut status_bar_text, mut transform)) = text_query.iter_mut().next() {
         status_bar_text.sections[0].value = text;
         status_bar_text.sections[0].style.color = color;
-        return;
+        transform.translation = layout
+            .screen(Vec2::new(
+                OFFSET * 2.0,
+                layout.height - (STATUS_BAR_HEIGHT * 0.5),
+            ))
+            .extend(0.0);
     }
 }
 
-pub fn draw_ui(mut gz: Gizmos, mouse_plot: Res<MousePlot>, area: Res<Area>) {
+/// axis values in real units of active curve range, at grid lines of view.
+/// Not used labels are empty
+pub fn draw_axis_labels(
+    mut labels: Query<(&mut Text, &mut Transform, &AxisLabel)>,
+    area: Res<Area>,
+    view: Res<View>,
+    layout: Res<Layout>,
+    snap: Res<Snap>,
+    settings: Res<Settings>,
+) {
+    let (lines_x, lines_y) = view.grid(area.range(), snap.step, settings.grid_lines);
+
+    for (mut text, mut transform, label) in &mut labels {
+        let (line, at) = match label {
+            AxisLabel::X(ind) => match lines_x.get(*ind) {
+                // below plot bottom line
+                Some(line) => (
+                    line,
+                    plot(&layout, &view, Vec2::new(line.at, view.min.y)) - Vec2::new(0.0, 4.0),
+                ),
+                None => {
+                    text.sections[0].value.clear();
+                    continue;
+                }
+            },
+            AxisLabel::Y(ind) => match lines_y.get(*ind) {
+                // above grid line, at plot left side
+                Some(line) => (
+                    line,
+                    plot(&layout, &view, Vec2::new(view.min.x, line.at)) + Vec2::new(4.0, 2.0),
+                ),
+                None => {
+                    text.sections[0].value.clear();
+                    continue;
+                }
+            },
+        };
+
+        text.sections[0].value = format_label(line.value);
+        transform.translation = at.extend(0.0);
+    }
+}
+
+/// short number, without trailing zeros
+fn format_label(v: f32) -> String {
+    let text = format!("{:.2}", v);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+
+    match text {
+        "-0" => String::from("0"),
+        _ => String::from(text),
+    }
+}
+
+pub fn draw_ui(
+    mut gz: Gizmos,
+    mouse_plot: Res<MousePlot>,
+    area: Res<Area>,
+    view: Res<View>,
+    layout: Res<Layout>,
+    settings: Res<Settings>,
+) {
+    // rubber band selection
+    if let Some((from, to)) = area.band {
+        let (a, b) = (plot(&layout, &view, from), plot(&layout, &view, to));
+        draw_box(&mut gz, a.min(b), a.max(b), color_light(0.6));
+    }
+
     if area.has_moving_points() {
         return;
     }
 
     // highlight selectable points
-    let closest = area.closest(mouse_plot.coords);
-    let dist = Vec2::new(closest.commited.x, closest.commited.y).distance(mouse_plot.coords);
-    if dist <= ACTIVE_RADIUS {
-        gz.circle_2d(plot(closest.commited), 10.0, Color::YELLOW_GREEN);
+    let hovered = area
+        .closest(mouse_plot.coords, &view)
+        .filter(|p| view.distance(p.commited, mouse_plot.coords) <= settings.active_radius);
+    if let Some(closest) = hovered {
+        gz.circle_2d(
+            plot(&layout, &view, closest.commited),
+            10.0,
+            Color::YELLOW_GREEN,
+        );
     }
 
     // interpolated pos of possible new point
     let new_point_ghost = area.interpolate(mouse_plot.coords.x);
-    if mouse_plot.coords.distance(new_point_ghost) <= (ACTIVE_RADIUS * 1.1) {
-        draw_box(
+    if view.distance(mouse_plot.coords, new_point_ghost) <= (settings.active_radius * 1.1) {
+        draw_point(
             &mut gz,
-            plot(new_point_ghost) - Vec2::splat(5.0),
-            plot(new_point_ghost) + Vec2::splat(5.0),
+            &layout,
+            &view,
+            new_point_ghost,
+            Vec2::splat(5.0),
             Color::SEA_GREEN,
         );
     }
@@ -194,6 +435,25 @@ fn color_axis_x(v: f32) -> Color {
     Color::hsl(v * 360.0, 0.75, 0.5)
 }
 
+fn color_curve(hues: &[f32], ind: usize, lightness: f32) -> Color {
+    Color::hsl(hues[ind % hues.len()], 0.75, lightness)
+}
+
 fn color_light(lightness: f32) -> Color {
     Color::hsl(0.0, 0.0, lightness)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn format_label_test() {
+        assert_eq!(format_label(0.0), "0");
+        assert_eq!(format_label(-0.001), "0");
+        assert_eq!(format_label(12.5), "12.5");
+        assert_eq!(format_label(-50.0), "-50");
+        assert_eq!(format_label(0.25), "0.25");
+        assert_eq!(format_label(1.0 / 3.0), "0.33");
+    }
+}
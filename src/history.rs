@@ -0,0 +1,432 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use spline_curve::{Curve, Interpolation, Range, Tangent};
+
+use crate::consts::HISTORY_LIMIT;
+use crate::resources::Area;
+
+/// Reversible change of area points.
+/// User interactions should push commands (with apply/revert code), instead of direct data change.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Insert(Vec2),
+    Delete {
+        at: Vec2,
+        tangent: Tangent,
+    },
+    /// selected points moved together, pairs of (from, to)
+    MoveGroup(Vec<(Vec2, Vec2)>),
+    SetTangent {
+        at: Vec2,
+        from: Tangent,
+        to: Tangent,
+    },
+    ReplaceAll {
+        before: Curve,
+        after: Curve,
+    },
+    SetInterpolation {
+        from: Interpolation,
+        to: Interpolation,
+    },
+    /// real units of active curve (None is 0..1)
+    SetRange {
+        from: Option<Range>,
+        to: Option<Range>,
+    },
+    /// whole document (opened file, pasted curves), `active` is curve index before change
+    ReplaceDocument {
+        before: Vec<Curve>,
+        active: usize,
+        after: Vec<Curve>,
+    },
+    AddCurve(Curve),
+    /// several commands as one history step (reverted in reverse order)
+    Group(Vec<Command>),
+}
+
+impl Command {
+    /// returns false, when nothing changed (command will not be stored in history)
+    fn apply(&self, area: &mut Area) -> bool {
+        match self {
+            Command::Insert(p) => area.insert(*p, false),
+            Command::Delete { at, tangent: _ } => area.delete(*at),
+            Command::MoveGroup(moves) => area.relocate_group(moves),
+            Command::SetTangent { at, from: _, to } => area.set_tangent(*at, *to),
+            Command::ReplaceAll { before: _, after } => {
+                area.replace(after);
+                true
+            }
+            Command::SetInterpolation { from: _, to } => area.set_interpolation(*to),
+            Command::SetRange { from: _, to } => area.set_range(*to),
+            Command::ReplaceDocument { after, .. } => {
+                area.replace_document(after, 0);
+                true
+            }
+            Command::AddCurve(curve) => {
+                area.add_curve(curve);
+                true
+            }
+            Command::Group(commands) => {
+                let mut changed = false;
+                for cmd in commands {
+                    changed |= cmd.apply(area);
+                }
+                changed
+            }
+        }
+    }
+
+    fn revert(&self, area: &mut Area) {
+        match self {
+            Command::Insert(p) => {
+                area.delete(*p);
+            }
+            Command::Delete { at, tangent } => {
+                area.insert(*at, false);
+                area.set_tangent(*at, *tangent);
+            }
+            Command::MoveGroup(moves) => {
+                let back: Vec<(Vec2, Vec2)> = moves.iter().map(|(from, to)| (*to, *from)).collect();
+                area.relocate_group(&back);
+            }
+            Command::SetTangent { at, from, to: _ } => {
+                area.set_tangent(*at, *from);
+            }
+            Command::ReplaceAll { before, after: _ } => area.replace(before),
+            Command::SetInterpolation { from, to: _ } => {
+                area.set_interpolation(*from);
+            }
+            Command::SetRange { from, to: _ } => {
+                area.set_range(*from);
+            }
+            Command::ReplaceDocument { before, active, .. } => {
+                area.replace_document(before, *active);
+            }
+            Command::AddCurve(_) => {
+                area.pop_curve();
+            }
+            Command::Group(commands) => {
+                for cmd in commands.iter().rev() {
+                    cmd.revert(area);
+                }
+            }
+        }
+    }
+}
+
+/// Bounded undo/redo stack.
+/// Commands are stored with index of curve they were applied to,
+/// undo/redo switches to this curve first.
+/// `saved` is history position (count of applied commands) of last saved state,
+/// None when this state is not reachable anymore.
+#[derive(Resource)]
+pub struct History {
+    done: VecDeque<(usize, Command)>,
+    undone: Vec<(usize, Command)>,
+    saved: Option<usize>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            done: VecDeque::new(),
+            undone: vec![],
+            saved: Some(0),
+        }
+    }
+}
+
+impl History {
+    pub fn apply(&mut self, area: &mut Area, cmd: Command) {
+        let curve = area.active;
+        if !cmd.apply(area) {
+            return;
+        }
+
+        // saved state was in redo branch, that will be lost now
+        if self.saved.is_some_and(|pos| pos > self.done.len()) {
+            self.saved = None;
+        }
+
+        self.undone.clear();
+        self.done.push_back((curve, cmd));
+
+        if self.done.len() > HISTORY_LIMIT {
+            self.done.pop_front();
+            self.saved = self.saved.and_then(|pos| pos.checked_sub(1));
+        }
+    }
+
+    pub fn undo(&mut self, area: &mut Area) -> bool {
+        let (curve, cmd) = match self.done.pop_back() {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        area.switch(curve);
+        cmd.revert(area);
+        self.undone.push((curve, cmd));
+        true
+    }
+
+    pub fn redo(&mut self, area: &mut Area) -> bool {
+        let (curve, cmd) = match self.undone.pop() {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        area.switch(curve);
+        cmd.apply(area);
+        self.done.push_back((curve, cmd));
+        true
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.saved = Some(self.done.len());
+    }
+
+    pub fn is_saved(&self) -> bool {
+        self.saved == Some(self.done.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn xs(area: &Area) -> Vec<f32> {
+        let mut xs: Vec<f32> = area.points.iter().map(|p| p.commited.x).collect();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        xs
+    }
+
+    #[test]
+    pub fn undo_redo_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        let initial = xs(&area);
+
+        history.apply(&mut area, Command::Insert(Vec2::new(0.5, 0.5)));
+        history.apply(
+            &mut area,
+            Command::MoveGroup(vec![(Vec2::new(0.5, 0.5), Vec2::new(0.6, 0.7))]),
+        );
+        let edited = xs(&area);
+        assert_eq!(edited.len(), initial.len() + 1);
+        assert!(edited.contains(&0.6));
+
+        assert!(history.undo(&mut area));
+        assert!(history.undo(&mut area));
+        assert!(!history.undo(&mut area));
+        assert_eq!(xs(&area), initial);
+
+        assert!(history.redo(&mut area));
+        assert!(history.redo(&mut area));
+        assert!(!history.redo(&mut area));
+        assert_eq!(xs(&area), edited);
+    }
+
+    #[test]
+    pub fn replace_all_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        let initial = area.curve();
+
+        let pasted = Curve::decode("#interp=linear\n0.0:0.0\n1.0:1.0\n").unwrap();
+        let before = area.curve();
+        history.apply(
+            &mut area,
+            Command::ReplaceAll {
+                before,
+                after: pasted.clone(),
+            },
+        );
+        assert_eq!(area.curve(), pasted);
+
+        history.undo(&mut area);
+        assert_eq!(area.curve(), initial);
+        assert_eq!(area.interpolation, Interpolation::CatmullRom);
+    }
+
+    #[test]
+    pub fn delete_restore_tangent_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        let at = Vec2::new(0.4, 0.5);
+        let tangent = Tangent::free(1.0, -1.0);
+
+        history.apply(
+            &mut area,
+            Command::SetTangent {
+                at,
+                from: Tangent::default(),
+                to: tangent,
+            },
+        );
+        history.apply(&mut area, Command::Delete { at, tangent });
+        history.undo(&mut area);
+
+        let restored = area.points.iter().find(|p| p.commited == at).unwrap();
+        assert_eq!(restored.tangent, tangent);
+        assert_eq!(restored.uncommited_tangent, tangent);
+    }
+
+    #[test]
+    pub fn ignore_noop_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+
+        // start/end points can`t be deleted
+        history.apply(
+            &mut area,
+            Command::Delete {
+                at: Vec2::new(0.0, 0.5),
+                tangent: Tangent::default(),
+            },
+        );
+        assert!(history.is_saved());
+        assert!(!history.undo(&mut area));
+    }
+
+    #[test]
+    pub fn saved_position_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        assert!(history.is_saved());
+
+        history.apply(&mut area, Command::Insert(Vec2::new(0.5, 0.5)));
+        assert!(!history.is_saved());
+
+        history.mark_saved();
+        history.apply(
+            &mut area,
+            Command::Delete {
+                at: Vec2::new(0.5, 0.5),
+                tangent: Tangent::default(),
+            },
+        );
+        assert!(!history.is_saved());
+
+        history.undo(&mut area);
+        assert!(history.is_saved());
+
+        // saved state lost in redo branch
+        history.undo(&mut area);
+        history.apply(&mut area, Command::Insert(Vec2::new(0.3, 0.3)));
+        assert!(!history.is_saved());
+        history.undo(&mut area);
+        assert!(!history.is_saved());
+    }
+
+    #[test]
+    pub fn group_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        let initial = area.curve();
+
+        // point takes place of other point of same group
+        let moves = vec![
+            (Vec2::new(0.2, 0.3), Vec2::new(0.4, 0.5)),
+            (Vec2::new(0.4, 0.5), Vec2::new(0.6, 0.7)),
+        ];
+        history.apply(&mut area, Command::MoveGroup(moves));
+        assert_eq!(xs(&area), vec![0.0, 0.4, 0.6, 0.8, 0.85, 1.0]);
+
+        history.apply(
+            &mut area,
+            Command::Group(vec![
+                Command::Delete {
+                    at: Vec2::new(0.4, 0.5),
+                    tangent: Tangent::default(),
+                },
+                Command::Delete {
+                    at: Vec2::new(0.6, 0.7),
+                    tangent: Tangent::default(),
+                },
+            ]),
+        );
+        assert_eq!(xs(&area), vec![0.0, 0.8, 0.85, 1.0]);
+
+        history.undo(&mut area);
+        history.undo(&mut area);
+        assert_eq!(area.curve(), initial);
+    }
+
+    #[test]
+    pub fn curves_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        let first = area.curve();
+
+        let second = Curve::decode("#name=fov\n0.0:0.0\n1.0:1.0\n").unwrap();
+        history.apply(&mut area, Command::AddCurve(second.clone()));
+        assert_eq!(area.active, 1);
+
+        // edit second curve, then switch back to first
+        history.apply(&mut area, Command::Insert(Vec2::new(0.5, 0.5)));
+        assert!(area.switch(0));
+        assert_eq!(area.curve(), first);
+        assert_eq!(area.layers().count(), 1);
+
+        // undo edits curve where command was applied
+        history.undo(&mut area);
+        assert_eq!(area.active, 1);
+        assert_eq!(area.curve(), second);
+        assert_eq!(area.document(), vec![first.clone(), second.clone()]);
+
+        history.undo(&mut area);
+        assert_eq!(area.document(), vec![first.clone()]);
+        assert_eq!(area.active, 0);
+
+        history.redo(&mut area);
+        history.redo(&mut area);
+        assert_eq!(area.active, 1);
+        assert_eq!(area.points.len(), 3);
+    }
+
+    #[test]
+    pub fn replace_document_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+        let initial = area.document();
+
+        let curves = vec![
+            Curve::decode("0.0:0.0\n1.0:1.0\n").unwrap(),
+            Curve::decode("0.0:1.0\n1.0:0.0\n").unwrap(),
+        ];
+        history.apply(
+            &mut area,
+            Command::ReplaceDocument {
+                before: initial.clone(),
+                active: 0,
+                after: curves.clone(),
+            },
+        );
+        assert_eq!(area.document(), curves);
+        assert_eq!(area.active, 0);
+
+        history.undo(&mut area);
+        assert_eq!(area.document(), initial);
+    }
+
+    #[test]
+    pub fn bounded_test() {
+        let mut area = Area::new();
+        let mut history = History::default();
+
+        for n in 0..(HISTORY_LIMIT + 10) {
+            let p = Vec2::new(0.001 * (n + 1) as f32, 0.5);
+            history.apply(&mut area, Command::Insert(p));
+        }
+
+        let mut undone = 0;
+        while history.undo(&mut area) {
+            undone += 1;
+        }
+
+        assert_eq!(undone, HISTORY_LIMIT);
+        assert!(!history.is_saved());
+    }
+}
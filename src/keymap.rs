@@ -0,0 +1,543 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Editor command bound to key chord (see `Keymap`).
+/// Names are used in `keys` of settings file
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    Copy,
+    CopyCss,
+    Paste,
+    CodegenRust,
+    CodegenGlsl,
+    CodegenWgsl,
+    Undo,
+    Redo,
+    Save,
+    SaveAs,
+    EditRange,
+    EditPoint,
+    AddCurve,
+    NextCurve,
+    PrevCurve,
+    Interpolation,
+    TangentMode,
+    ClipboardFormat,
+    Bake,
+    Render,
+    FrameAll,
+    Fullscreen,
+    Delete,
+    InsertPoint,
+    FocusPrev,
+    FocusNext,
+    NudgeLeft,
+    NudgeRight,
+    NudgeUp,
+    NudgeDown,
+    GridSnap,
+    PointSnap,
+    CurveSnap,
+    SnapStep,
+    // held while mouse drags points
+    AxisLockX,
+    AxisLockY,
+    Snap,
+    AddToSelection,
+    // held while nudging
+    NudgeCoarse,
+    NudgeFine,
+}
+
+/// when held actions are checked, same chord can be used in different contexts
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Context {
+    /// pressed once
+    Trigger,
+    Drag,
+    Nudge,
+}
+
+/// key with modifiers, `Ctrl+Shift+S`. Modifiers match left and right keys.
+/// Chord without key is modifiers only (held actions, `Ctrl`)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Chord {
+    pub key: Option<KeyCode>,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// command key on macOS, windows key
+    pub super_key: bool,
+}
+
+/// Bindings of all actions, defaults with overrides of settings file
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct Keymap {
+    bindings: HashMap<Action, Chord>,
+}
+
+/// Keyboard state through keymap, systems ask for actions instead of keys
+#[derive(SystemParam)]
+pub struct Keys<'w> {
+    keymap: Res<'w, Keymap>,
+    keyboard: Res<'w, Input<KeyCode>>,
+}
+
+const KEY_NAMES: [(&str, KeyCode); 68] = [
+    ("A", KeyCode::A),
+    ("B", KeyCode::B),
+    ("C", KeyCode::C),
+    ("D", KeyCode::D),
+    ("E", KeyCode::E),
+    ("F", KeyCode::F),
+    ("G", KeyCode::G),
+    ("H", KeyCode::H),
+    ("I", KeyCode::I),
+    ("J", KeyCode::J),
+    ("K", KeyCode::K),
+    ("L", KeyCode::L),
+    ("M", KeyCode::M),
+    ("N", KeyCode::N),
+    ("O", KeyCode::O),
+    ("P", KeyCode::P),
+    ("Q", KeyCode::Q),
+    ("R", KeyCode::R),
+    ("S", KeyCode::S),
+    ("T", KeyCode::T),
+    ("U", KeyCode::U),
+    ("V", KeyCode::V),
+    ("W", KeyCode::W),
+    ("X", KeyCode::X),
+    ("Y", KeyCode::Y),
+    ("Z", KeyCode::Z),
+    ("0", KeyCode::Key0),
+    ("1", KeyCode::Key1),
+    ("2", KeyCode::Key2),
+    ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4),
+    ("5", KeyCode::Key5),
+    ("6", KeyCode::Key6),
+    ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8),
+    ("9", KeyCode::Key9),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Insert", KeyCode::Insert),
+    ("Delete", KeyCode::Delete),
+    ("Backspace", KeyCode::Back),
+    ("Tab", KeyCode::Tab),
+    ("Enter", KeyCode::Return),
+    ("Space", KeyCode::Space),
+    ("Escape", KeyCode::Escape),
+    ("Minus", KeyCode::Minus),
+    ("Equals", KeyCode::Equals),
+    ("Comma", KeyCode::Comma),
+    ("Period", KeyCode::Period),
+    ("Slash", KeyCode::Slash),
+];
+
+impl Action {
+    pub const ALL: [Action; 40] = [
+        Action::Copy,
+        Action::CopyCss,
+        Action::Paste,
+        Action::CodegenRust,
+        Action::CodegenGlsl,
+        Action::CodegenWgsl,
+        Action::Undo,
+        Action::Redo,
+        Action::Save,
+        Action::SaveAs,
+        Action::EditRange,
+        Action::EditPoint,
+        Action::AddCurve,
+        Action::NextCurve,
+        Action::PrevCurve,
+        Action::Interpolation,
+        Action::TangentMode,
+        Action::ClipboardFormat,
+        Action::Bake,
+        Action::Render,
+        Action::FrameAll,
+        Action::Fullscreen,
+        Action::Delete,
+        Action::InsertPoint,
+        Action::FocusPrev,
+        Action::FocusNext,
+        Action::NudgeLeft,
+        Action::NudgeRight,
+        Action::NudgeUp,
+        Action::NudgeDown,
+        Action::GridSnap,
+        Action::PointSnap,
+        Action::CurveSnap,
+        Action::SnapStep,
+        Action::AxisLockX,
+        Action::AxisLockY,
+        Action::Snap,
+        Action::AddToSelection,
+        Action::NudgeCoarse,
+        Action::NudgeFine,
+    ];
+
+    pub fn default_chord(self) -> &'static str {
+        match self {
+            Action::Copy => "Ctrl+C",
+            Action::CopyCss => "Ctrl+Shift+C",
+            Action::Paste => "Ctrl+V",
+            Action::CodegenRust => "Ctrl+G",
+            Action::CodegenGlsl => "Ctrl+Shift+G",
+            Action::CodegenWgsl => "Ctrl+Alt+G",
+            Action::Undo => "Ctrl+Z",
+            Action::Redo => "Ctrl+Shift+Z",
+            Action::Save => "Ctrl+S",
+            Action::SaveAs => "Ctrl+Shift+S",
+            Action::EditRange => "Ctrl+E",
+            Action::EditPoint => "Enter",
+            Action::AddCurve => "Ctrl+N",
+            Action::NextCurve => "Tab",
+            Action::PrevCurve => "Shift+Tab",
+            Action::Interpolation => "I",
+            Action::TangentMode => "T",
+            Action::ClipboardFormat => "F",
+            Action::Bake => "Ctrl+B",
+            Action::Render => "Ctrl+R",
+            Action::FrameAll => "Home",
+            Action::Fullscreen => "F11",
+            Action::Delete => "Delete",
+            Action::InsertPoint => "Insert",
+            Action::FocusPrev => "Left",
+            Action::FocusNext => "Right",
+            Action::NudgeLeft => "Shift+Left",
+            Action::NudgeRight => "Shift+Right",
+            Action::NudgeUp => "Shift+Up",
+            Action::NudgeDown => "Shift+Down",
+            Action::GridSnap => "G",
+            Action::PointSnap => "P",
+            Action::CurveSnap => "L",
+            Action::SnapStep => "Shift+G",
+            Action::AxisLockX => "X",
+            Action::AxisLockY => "Y",
+            Action::Snap => "Ctrl",
+            Action::AddToSelection => "Shift",
+            Action::NudgeCoarse => "Ctrl",
+            Action::NudgeFine => "Alt",
+        }
+    }
+
+    fn context(self) -> Context {
+        match self {
+            Action::AxisLockX | Action::AxisLockY | Action::Snap | Action::AddToSelection => {
+                Context::Drag
+            }
+            Action::NudgeCoarse | Action::NudgeFine => Context::Nudge,
+            _ => Context::Trigger,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Chord {
+    fn modifiers(&self) -> usize {
+        [self.ctrl, self.shift, self.alt, self.super_key]
+            .iter()
+            .filter(|m| **m)
+            .count()
+    }
+
+    /// all modifiers of chord are held (other modifiers can be held too)
+    fn modifiers_held(&self, keyboard: &Input<KeyCode>) -> bool {
+        let held = |left, right| keyboard.any_pressed([left, right]);
+
+        (!self.ctrl || held(KeyCode::ControlLeft, KeyCode::ControlRight))
+            && (!self.shift || held(KeyCode::ShiftLeft, KeyCode::ShiftRight))
+            && (!self.alt || held(KeyCode::AltLeft, KeyCode::AltRight))
+            && (!self.super_key || held(KeyCode::SuperLeft, KeyCode::SuperRight))
+    }
+
+    fn just_pressed(&self, keyboard: &Input<KeyCode>) -> bool {
+        self.key.is_some_and(|key| keyboard.just_pressed(key)) && self.modifiers_held(keyboard)
+    }
+
+    fn pressed(&self, keyboard: &Input<KeyCode>) -> bool {
+        self.key.map_or(true, |key| keyboard.pressed(key)) && self.modifiers_held(keyboard)
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chord = Chord::default();
+
+        for part in s.split('+').map(|p| p.trim()) {
+            let modifier = match part.to_lowercase().as_str() {
+                "ctrl" | "control" => &mut chord.ctrl,
+                "shift" => &mut chord.shift,
+                "alt" | "option" => &mut chord.alt,
+                "super" | "cmd" | "command" | "win" | "meta" => &mut chord.super_key,
+                _ => {
+                    if chord.key.is_some() {
+                        return Err(format!("{}: more than one key", s));
+                    }
+
+                    let key = KEY_NAMES
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(part))
+                        .map(|(_, key)| *key);
+                    chord.key = Some(key.ok_or(format!("{}: unknown key {:?}", s, part))?);
+                    continue;
+                }
+            };
+
+            if *modifier {
+                return Err(format!("{}: repeated modifier", s));
+            }
+            *modifier = true;
+        }
+
+        if chord.key.is_none() && chord.modifiers() == 0 {
+            return Err(String::from("empty key chord"));
+        }
+
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        for (held, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.shift, "Shift"),
+            (self.alt, "Alt"),
+            (self.super_key, "Super"),
+        ] {
+            if held {
+                parts.push(name);
+            }
+        }
+
+        let key = self
+            .key
+            .and_then(|key| KEY_NAMES.iter().find(|(_, code)| *code == key));
+        if let Some((name, _)) = key {
+            parts.push(name);
+        }
+
+        write!(f, "{}", parts.join("+"))
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).unwrap()
+    }
+}
+
+impl Keymap {
+    /// default bindings with `overrides` (action -> chord, `"Ctrl+Shift+S"`),
+    /// error on unknown keys and on ambiguous bindings
+    pub fn new(overrides: &BTreeMap<Action, String>) -> Result<Self, String> {
+        let mut bindings = HashMap::new();
+        for action in Action::ALL {
+            let text = match overrides.get(&action) {
+                Some(text) => text.as_str(),
+                None => action.default_chord(),
+            };
+            let chord = text
+                .parse::<Chord>()
+                .map_err(|err| format!("key of {}: {}", action, err))?;
+
+            if action.context() == Context::Trigger && chord.key.is_none() {
+                return Err(format!("key of {}: {} has no key", action, chord));
+            }
+            bindings.insert(action, chord);
+        }
+
+        let keymap = Self { bindings };
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    /// same chord of two actions can`t be resolved,
+    /// held actions of different contexts (drag, nudge) are never checked together
+    fn check_conflicts(&self) -> Result<(), String> {
+        for (ind, a) in Action::ALL.iter().enumerate() {
+            for b in Action::ALL.iter().skip(ind + 1) {
+                let (ca, cb) = (a.context(), b.context());
+                if ca != cb && ca != Context::Trigger && cb != Context::Trigger {
+                    continue;
+                }
+
+                if self.chord(*a) == self.chord(*b) {
+                    return Err(format!(
+                        "{} and {} are both bound to {}",
+                        a,
+                        b,
+                        self.chord(*a)
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn chord(&self, action: Action) -> Chord {
+        self.bindings[&action]
+    }
+
+    /// action chord is just pressed, and no more specific chord with same key
+    /// (`Ctrl+Shift+S` is "save as", not "save" too)
+    pub fn just_pressed(&self, action: Action, keyboard: &Input<KeyCode>) -> bool {
+        let chord = self.chord(action);
+        if !chord.just_pressed(keyboard) {
+            return false;
+        }
+
+        !self.bindings.values().any(|other| {
+            other.key == chord.key
+                && other.modifiers() > chord.modifiers()
+                && other.just_pressed(keyboard)
+        })
+    }
+
+    pub fn pressed(&self, action: Action, keyboard: &Input<KeyCode>) -> bool {
+        self.chord(action).pressed(keyboard)
+    }
+}
+
+impl<'w> Keys<'w> {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.keymap.just_pressed(action, &self.keyboard)
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.keymap.pressed(action, &self.keyboard)
+    }
+
+    pub fn chord(&self, action: Action) -> Chord {
+        self.keymap.chord(action)
+    }
+
+    /// not rebindable keys of status bar text input (enter, escape, backspace)
+    pub fn prompt_key(&self, key: KeyCode) -> bool {
+        self.keyboard.just_pressed(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keyboard(keys: &[KeyCode]) -> Input<KeyCode> {
+        let mut input = Input::default();
+        for key in keys {
+            input.press(*key);
+        }
+        input
+    }
+
+    #[test]
+    pub fn chord_test() {
+        let chord = "Ctrl+Shift+S".parse::<Chord>().unwrap();
+        assert_eq!(chord.key, Some(KeyCode::S));
+        assert!(chord.ctrl && chord.shift && !chord.alt);
+        assert_eq!(chord.to_string(), "Ctrl+Shift+S");
+
+        assert_eq!("cmd + c".parse::<Chord>().unwrap().to_string(), "Super+C");
+        assert_eq!("Ctrl".parse::<Chord>().unwrap().key, None);
+
+        assert!("Ctrl+Q+W".parse::<Chord>().is_err());
+        assert!("Ctrl+Ctrl+W".parse::<Chord>().is_err());
+        assert!("Hyper+W".parse::<Chord>().is_err());
+        assert!("".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    pub fn keymap_test() {
+        let keymap = Keymap::default();
+
+        // right ctrl works too, most specific chord wins
+        let input = keyboard(&[KeyCode::ControlRight, KeyCode::S]);
+        assert!(keymap.just_pressed(Action::Save, &input));
+        assert!(!keymap.just_pressed(Action::SaveAs, &input));
+
+        let input = keyboard(&[KeyCode::ControlLeft, KeyCode::ShiftLeft, KeyCode::S]);
+        assert!(keymap.just_pressed(Action::SaveAs, &input));
+        assert!(!keymap.just_pressed(Action::Save, &input));
+
+        // nudge with coarse modifier, focus is not moved
+        let input = keyboard(&[KeyCode::ControlLeft, KeyCode::ShiftLeft, KeyCode::Left]);
+        assert!(keymap.just_pressed(Action::NudgeLeft, &input));
+        assert!(keymap.pressed(Action::NudgeCoarse, &input));
+        assert!(!keymap.just_pressed(Action::FocusPrev, &input));
+
+        // held keys
+        let mut input = keyboard(&[KeyCode::X]);
+        input.clear();
+        assert!(keymap.pressed(Action::AxisLockX, &input));
+        assert!(!keymap.just_pressed(Action::AxisLockX, &input));
+        assert!(!keymap.pressed(Action::Snap, &input));
+    }
+
+    #[test]
+    pub fn overrides_test() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert(Action::Copy, String::from("Super+C"));
+        overrides.insert(Action::Snap, String::from("Super"));
+        let keymap = Keymap::new(&overrides).unwrap();
+
+        let input = keyboard(&[KeyCode::SuperLeft, KeyCode::C]);
+        assert!(keymap.just_pressed(Action::Copy, &input));
+        assert!(keymap.pressed(Action::Snap, &input));
+        let input = keyboard(&[KeyCode::ControlLeft, KeyCode::C]);
+        assert!(!keymap.just_pressed(Action::Copy, &input));
+
+        // ambiguous bindings
+        overrides.insert(Action::Paste, String::from("Super+C"));
+        let err = Keymap::new(&overrides).unwrap_err();
+        assert_eq!(err, "Copy and Paste are both bound to Super+C");
+
+        // held keys of different contexts can be same, but not of same context
+        let mut overrides = BTreeMap::new();
+        overrides.insert(Action::NudgeFine, String::from("Ctrl"));
+        assert!(Keymap::new(&overrides).is_err());
+        overrides.insert(Action::NudgeFine, String::from("Shift"));
+        assert!(Keymap::new(&overrides).is_ok());
+
+        // pressed once actions need a key
+        let mut overrides = BTreeMap::new();
+        overrides.insert(Action::Undo, String::from("Ctrl"));
+        assert!(Keymap::new(&overrides).is_err());
+    }
+}
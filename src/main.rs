@@ -1,47 +1,98 @@
+mod cli;
 mod consts;
 mod draw;
-mod encoders;
+mod history;
+mod keymap;
+mod render;
 mod resources;
-mod spline;
+mod settings;
 
 use std::path::PathBuf;
 
 use arboard::Clipboard;
 use bevy::{
     core_pipeline::clear_color::ClearColorConfig,
+    input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
     text::{Text, Text2dBundle},
     utils::default,
-    window::{FileDragAndDrop, PresentMode, Window, WindowMode, WindowPlugin},
+    window::{
+        FileDragAndDrop, PresentMode, PrimaryWindow, ReceivedCharacter, Window, WindowMode,
+        WindowPlugin, WindowResizeConstraints,
+    },
     DefaultPlugins,
 };
 use bevy_embedded_assets::EmbeddedAssetPlugin;
 use consts::*;
 use draw::*;
-use encoders::{decode, encode};
+use history::*;
+use keymap::*;
+use render::render;
 use resources::*;
+use settings::*;
+use spline_curve::{
+    codegen, codegen_lut, encode_css, Curve, Format, Interpolation, Language, Range, Tangent,
+    TangentMode,
+};
 
 // Some fasty-shitty code here, but its works fine..
 // In case of refactoring wish:
 // - remove plotWH and left only windowWH (not need to scale all to *2)
 // - separate modules with events (instead of spaghetti code)
-// - add CQRS (user interactions should push migration actions (with apply/revert code), instead of direct data change)
-// - - add undo/redo
 
 #[derive(Component)]
 pub struct StatusBarTextMarker;
 
+/// text of axis value, index of grid line in view (see `draw_axis_labels`)
+#[derive(Component)]
+pub enum AxisLabel {
+    X(usize),
+    Y(usize),
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
+    // not a subcommand, so it is file to open
+    let launch_args = LaunchArgs {
+        file: args.first().map(PathBuf::from),
+    };
+
+    // defaults without settings file, invalid file is reported in status bar
+    let mut status_bar = StatusBar::default();
+    let mut settings_file = SettingsFile::new(settings_path());
+    let settings = match load_settings(&mut settings_file) {
+        Some(Ok(settings)) => settings,
+        Some(Err(err)) => {
+            status_bar.show_error(err.as_str());
+            Settings::default()
+        }
+        None => Settings::default(),
+    };
+    let keymap = settings.keymap().unwrap_or_default();
+    let snap = Snap {
+        step: settings.snap_step(),
+        ..default()
+    };
+
     App::new()
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: String::from("Curve editor (ctrl+c/v for copy/paste data)"),
-                        resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
+                        resolution: settings.window_size().into(),
                         present_mode: PresentMode::AutoVsync,
                         mode: WindowMode::Windowed,
-                        resizable: false,
+                        resizable: true,
+                        resize_constraints: WindowResizeConstraints {
+                            min_width: MIN_WINDOW_WIDTH,
+                            min_height: MIN_WINDOW_HEIGHT,
+                            ..default()
+                        },
                         ..default()
                     }),
                     ..default()
@@ -51,26 +102,103 @@ fn main() {
         )
         .insert_resource(Area::new())
         .insert_resource(AttachedFile::default())
-        .insert_resource(StatusBar::default())
+        .insert_resource(status_bar)
         .insert_resource(MousePlot::default())
-        .add_systems(Startup, init)
-        .add_systems(Update, (file_attach, clipboard))
+        .insert_resource(View::default())
+        .insert_resource(Layout::default())
+        .insert_resource(History::default())
+        .insert_resource(Prompt::default())
+        .insert_resource(ClipboardFormat::default())
+        .insert_resource(snap)
+        .insert_resource(settings)
+        .insert_resource(keymap)
+        .insert_resource(settings_file)
+        .insert_resource(launch_args)
+        .add_systems(Startup, (init, open_launch_file))
+        .add_systems(PreUpdate, (update_layout, reload_settings))
+        .add_systems(Update, (file_attach, clipboard.run_if(prompt_closed)))
         .add_systems(
             Update,
             (
-                update_mouse_plot_coords,
-                select_points,
+                (zoom_pan, update_mouse_plot_coords).chain(),
+                toggle_fullscreen.run_if(prompt_closed),
+                (select_points, create_points).chain(),
                 delete_points,
-                create_points,
+                delete_selection.run_if(prompt_closed),
+                keyboard_edit.run_if(prompt_closed),
                 move_points,
+                drag_tangents,
+                cycle_tangent_mode.run_if(prompt_closed),
+                undo_redo.run_if(prompt_closed),
+                cycle_interpolation.run_if(prompt_closed),
+                cycle_clipboard_format.run_if(prompt_closed),
+                switch_curve.run_if(prompt_closed),
+                add_curve.run_if(prompt_closed),
                 set_dirty_state,
-                save_file,
+                save_file.run_if(prompt_closed),
+                toggle_snap.run_if(prompt_closed),
+                (
+                    prompt_input,
+                    edit_point,
+                    save_as,
+                    edit_range,
+                    edit_snap_step,
+                )
+                    .chain(),
+                bake_lut.run_if(prompt_closed),
+                render_preview.run_if(prompt_closed),
             ),
         )
-        .add_systems(Update, (draw_plot, draw_ui, draw_status_bar))
+        .add_systems(
+            Update,
+            (draw_plot, draw_ui, draw_status_bar, draw_axis_labels),
+        )
         .run();
 }
 
+/// settings from file, None when there is no file
+fn load_settings(file: &mut SettingsFile) -> Option<Result<Settings, String>> {
+    file.modified = file.modified();
+    let path = file.path.as_ref().filter(|_| file.modified.is_some())?;
+    let loaded = Settings::read(path);
+    Some(loaded.map_err(|err| format!("settings {}: {}", path.display(), err)))
+}
+
+/// hot reload, when settings file is changed (invalid file keeps current settings)
+fn reload_settings(
+    mut settings: ResMut<Settings>,
+    mut keymap: ResMut<Keymap>,
+    mut file: ResMut<SettingsFile>,
+    mut snap: ResMut<Snap>,
+    mut status_bar: ResMut<StatusBar>,
+    time: Res<Time>,
+) {
+    file.elapsed += time.delta_seconds();
+    if file.elapsed < SETTINGS_CHECK_INTERVAL {
+        return;
+    }
+    file.elapsed = 0.0;
+
+    if file.modified() == file.modified {
+        return;
+    }
+
+    match load_settings(&mut file) {
+        Some(Ok(loaded)) => {
+            if loaded.snap_step != settings.snap_step {
+                snap.step = loaded.snap_step();
+            }
+            // keys are validated with settings
+            *keymap = loaded.keymap().unwrap_or_default();
+            *settings = loaded;
+            status_bar.show_info("settings reloaded");
+        }
+        Some(Err(err)) => status_bar.show_error(err.as_str()),
+        // removed file keeps current settings
+        None => {}
+    }
+}
+
 fn init(mut cmd: Commands, asset_server: Res<AssetServer>) {
     let font = asset_server.load("fonts/font.ttf");
 
@@ -89,49 +217,76 @@ fn init(mut cmd: Commands, asset_server: Res<AssetServer>) {
                 "...",
                 bevy::text::TextStyle {
                     color: Color::DARK_GRAY,
-                    font: font,
+                    font: font.clone(),
                     font_size: 18.0,
                     ..default()
                 },
             ),
+            // placed by window size in `draw_status_bar`
             text_anchor: bevy::sprite::Anchor::CenterLeft,
-            transform: Transform::from_translation(
-                Vec2::new(
-                    -(WINDOW_WIDTH * 0.5) + (OFFSET * 2.0),
-                    -(WINDOW_HEIGHT * 0.5) + STATUS_BAR_HEIGHT - (STATUS_BAR_HEIGHT * 0.5),
-                )
-                .extend(0.0),
-            ),
             ..default()
         },
     ));
+
+    // pool of axis labels, placed at grid lines (see `draw_axis_labels`)
+    for ind in 0..LABELS_PER_AXIS {
+        cmd.spawn((
+            AxisLabel::X(ind),
+            axis_label(&font, bevy::sprite::Anchor::TopCenter),
+        ));
+        cmd.spawn((
+            AxisLabel::Y(ind),
+            axis_label(&font, bevy::sprite::Anchor::BottomLeft),
+        ));
+    }
+}
+
+fn axis_label(font: &Handle<Font>, anchor: bevy::sprite::Anchor) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(
+            "",
+            bevy::text::TextStyle {
+                color: Color::DARK_GRAY,
+                font: font.clone(),
+                font_size: LABEL_FONT_SIZE,
+                ..default()
+            },
+        ),
+        text_anchor: anchor,
+        ..default()
+    }
 }
 
 fn clipboard(
     mut area: ResMut<Area>,
+    mut history: ResMut<History>,
     mut status_bar: ResMut<StatusBar>,
-    keyboard: Res<Input<KeyCode>>,
+    file: Res<AttachedFile>,
+    clipboard_format: Res<ClipboardFormat>,
+    keys: Keys,
 ) {
-    if !keyboard.pressed(KeyCode::ControlLeft) {
-        return;
-    }
-
     #[derive(PartialEq, Eq)]
-    enum Action {
+    enum Act {
         Nothing,
         Copy,
+        CopyCss,
         Paste,
+        Codegen(Language),
     }
 
-    let mut act = Action::Nothing;
-    if keyboard.just_pressed(KeyCode::C) {
-        act = Action::Copy;
-    }
-    if keyboard.just_pressed(KeyCode::V) {
-        act = Action::Paste;
-    }
+    let act = [
+        (Action::Copy, Act::Copy),
+        (Action::CopyCss, Act::CopyCss),
+        (Action::Paste, Act::Paste),
+        (Action::CodegenRust, Act::Codegen(Language::Rust)),
+        (Action::CodegenGlsl, Act::Codegen(Language::Glsl)),
+        (Action::CodegenWgsl, Act::Codegen(Language::Wgsl)),
+    ]
+    .into_iter()
+    .find(|(action, _)| keys.just_pressed(*action))
+    .map_or(Act::Nothing, |(_, act)| act);
 
-    if act == Action::Nothing {
+    if act == Act::Nothing {
         return;
     }
 
@@ -144,36 +299,113 @@ fn clipboard(
     };
 
     match act {
-        Action::Copy => {
-            let mut data: Vec<Vec2> = vec![];
-            for p in area.points.clone() {
-                data.push(p.commited);
-            }
-            let content = encode(data);
+        Act::Copy => {
+            let content = area.encode(clipboard_format.format);
 
             match ctx.set_text(content) {
                 Err(err) => {
                     status_bar.show_error(format!("copy failed: {}", err).as_str());
                 }
+                // single curve formats have only active curve
+                _ if clipboard_format
+                    .format
+                    .check_document(&area.document())
+                    .is_err() =>
+                {
+                    status_bar.show_info(format!("copied only {}", area.curve_label()).as_str());
+                }
                 _ => {}
             };
         }
-        Action::Paste => match ctx.get_text() {
+        Act::CopyCss => {
+            // only single segment curve is css easing
+            let content = match encode_css(&area.curve()) {
+                Ok(content) => content,
+                Err(err) => {
+                    status_bar.show_error(err.as_str());
+                    return;
+                }
+            };
+
+            match ctx.set_text(content) {
+                Err(err) => {
+                    status_bar.show_error(format!("copy failed: {}", err).as_str());
+                }
+                _ => status_bar.show_info("css easing copied"),
+            };
+        }
+        Act::Paste => match ctx.get_text() {
             Ok(content) => {
-                apply_content(&mut area, &mut status_bar, &content);
+                let format = Format::detect(&content);
+                apply_content(
+                    &mut area,
+                    &mut history,
+                    &mut status_bar,
+                    &content,
+                    format,
+                    false,
+                );
             }
             Err(err) => {
                 status_bar.show_error(format!("can`t paste content: {}", err).as_str());
             }
         },
+        Act::Codegen(language) => {
+            let curve = area.curve();
+            let name = match file.attached {
+                true => PathBuf::from(&file.file_path)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                false => String::new(),
+            };
+
+            // bezier segments can`t be written as polynomials
+            let (content, info) = match codegen(&curve, &name, language) {
+                Ok(code) => (code, format!("{} code copied", language)),
+                Err(_) => (
+                    codegen_lut(&curve.bake(LUT_SIZE), &name, language),
+                    format!(
+                        "{} code copied (baked lut of {} values)",
+                        language, LUT_SIZE
+                    ),
+                ),
+            };
+
+            match ctx.set_text(content) {
+                Err(err) => {
+                    status_bar.show_error(format!("copy failed: {}", err).as_str());
+                }
+                _ => status_bar.show_info(info.as_str()),
+            };
+        }
         _ => {}
     }
 }
 
+fn open_launch_file(
+    mut area: ResMut<Area>,
+    mut file: ResMut<AttachedFile>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    launch_args: Res<LaunchArgs>,
+) {
+    if let Some(path_buf) = &launch_args.file {
+        attach_file(
+            path_buf,
+            &mut area,
+            &mut file,
+            &mut history,
+            &mut status_bar,
+        );
+    }
+}
+
 fn file_attach(
     mut area: ResMut<Area>,
     mut events: EventReader<FileDragAndDrop>,
     mut file: ResMut<AttachedFile>,
+    mut history: ResMut<History>,
     mut status_bar: ResMut<StatusBar>,
 ) {
     for ev in events.iter() {
@@ -182,61 +414,132 @@ fn file_attach(
                 window: _,
                 path_buf,
             } => {
-                let data = match std::fs::read(path_buf) {
-                    Ok(bytes) => bytes,
-                    Err(err) => {
-                        status_bar
-                            .show_error(format!("can`t open: {} ({:?})", err, path_buf).as_str());
-                        return;
-                    }
-                };
-
-                let data = match std::str::from_utf8(&data) {
-                    Ok(s) => s,
-                    Err(err) => {
-                        status_bar.show_error(
-                            format!("unexpected content: {} ({:?})", err, path_buf).as_str(),
-                        );
-                        return;
-                    }
-                };
-
-                let changed = apply_content(&mut area, &mut status_bar, data);
-                if !changed {
-                    return;
-                }
-
-                file.state = area.points.clone();
-                file.attached = true;
-                file.dirty = false;
-                file.file_path = path_buf.to_str().unwrap().to_string();
+                attach_file(
+                    path_buf,
+                    &mut area,
+                    &mut file,
+                    &mut history,
+                    &mut status_bar,
+                );
             }
             _ => {}
         }
     }
 }
 
-fn apply_content(area: &mut ResMut<Area>, status_bar: &mut ResMut<StatusBar>, data: &str) -> bool {
-    let content = match decode(String::from(data)) {
-        Ok(points) => points,
+fn attach_file(
+    path_buf: &PathBuf,
+    area: &mut ResMut<Area>,
+    file: &mut ResMut<AttachedFile>,
+    history: &mut ResMut<History>,
+    status_bar: &mut ResMut<StatusBar>,
+) {
+    let data = match std::fs::read(path_buf) {
+        Ok(bytes) => bytes,
         Err(err) => {
-            status_bar.show_error(format!("invalid format: {}", err).as_str());
+            status_bar.show_error(format!("can`t open: {} ({:?})", err, path_buf).as_str());
+            return;
+        }
+    };
+
+    let data = match std::str::from_utf8(&data) {
+        Ok(s) => s,
+        Err(err) => {
+            status_bar.show_error(format!("unexpected content: {} ({:?})", err, path_buf).as_str());
+            return;
+        }
+    };
+
+    let format = Format::from_path(&path_buf.to_string_lossy()).unwrap_or(Format::detect(data));
+    let changed = apply_content(area, history, status_bar, data, format, true);
+    if !changed {
+        return;
+    }
+
+    history.mark_saved();
+    file.attached = true;
+    file.file_path = path_buf.to_string_lossy().to_string();
+}
+
+/// single curve replaces active one (pasted), unless `document` (opened file) is set,
+/// several curves always replace whole document
+fn apply_content(
+    area: &mut ResMut<Area>,
+    history: &mut ResMut<History>,
+    status_bar: &mut ResMut<StatusBar>,
+    data: &str,
+    format: Format,
+    document: bool,
+) -> bool {
+    let mut content = match Area::decode(data, format) {
+        Ok(curves) => curves,
+        Err(err) => {
+            status_bar.show_error(err.as_str());
             return false;
         }
     };
 
-    let mut points: Vec<Point> = vec![];
-    for p in content {
-        points.push(Point::new(p.x, p.y));
+    if content.len() == 1 && !document {
+        let before = area.curve();
+        history.apply(
+            area,
+            Command::ReplaceAll {
+                before,
+                after: content.swap_remove(0),
+            },
+        );
+        return true;
     }
 
-    area.points = points;
+    let before = area.document();
+    let active = area.active;
+    history.apply(
+        area,
+        Command::ReplaceDocument {
+            before,
+            active,
+            after: content,
+        },
+    );
     return true;
 }
 
+fn update_layout(windows: Query<&Window, With<PrimaryWindow>>, mut layout: ResMut<Layout>) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let size = Layout {
+        width: window.width(),
+        height: window.height(),
+    };
+
+    // not touch resource without changes (change detection)
+    if *layout != size {
+        *layout = size;
+    }
+}
+
+fn toggle_fullscreen(mut windows: Query<&mut Window, With<PrimaryWindow>>, keys: Keys) {
+    if !keys.just_pressed(Action::Fullscreen) {
+        return;
+    }
+
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+        _ => WindowMode::Windowed,
+    };
+}
+
 fn update_mouse_plot_coords(
     mut cursor_moved_events: EventReader<CursorMoved>,
     mut mouse_res: ResMut<MousePlot>,
+    view: Res<View>,
+    layout: Res<Layout>,
 ) {
     for ev in cursor_moved_events.iter() {
         match ev {
@@ -244,59 +547,163 @@ fn update_mouse_plot_coords(
                 window: _,
                 position,
             } => {
-                let abs_pos_on_plot = *position - Vec2::splat(OFFSET * WINDOW_SCALE);
-                let rel_pos = abs_pos_on_plot
-                    / Vec2::new(
-                        (PLOT_WIDTH * WINDOW_SCALE) - (OFFSET * 2.0 * WINDOW_SCALE),
-                        (PLOT_HEIGHT * WINDOW_SCALE) - (OFFSET * 2.0 * WINDOW_SCALE),
-                    );
-
-                let rel_pos = rel_pos.clamp(Vec2::ZERO, Vec2::ONE);
-                mouse_res.coords = Vec2::new(roundf32(rel_pos.x, 2), 1.0 - roundf32(rel_pos.y, 2));
+                mouse_res.screen = layout.cursor(*position);
             }
         }
     }
+
+    // view can be changed without cursor move too
+    mouse_res.coords = view.cursor(mouse_res.screen);
 }
 
+/// mouse wheel zoom around cursor, middle mouse drag to pan, Home to frame all curves
+#[allow(clippy::too_many_arguments)]
+fn zoom_pan(
+    mut view: ResMut<View>,
+    mut wheel: EventReader<MouseWheel>,
+    mut drag_from: Local<Option<Vec2>>,
+    area: Res<Area>,
+    mouse_res: Res<MousePlot>,
+    settings: Res<Settings>,
+    mouse_input: Res<Input<MouseButton>>,
+    keys: Keys,
+) {
+    for ev in wheel.iter() {
+        let lines = match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y / 50.0,
+        };
+
+        let at = view.unproject(mouse_res.screen.clamp(Vec2::ZERO, Vec2::ONE));
+        view.zoom(at, settings.zoom_step.powf(lines));
+    }
+
+    // curve point under cursor stays under cursor while dragging
+    if mouse_input.just_pressed(MouseButton::Middle) {
+        *drag_from = Some(mouse_res.screen);
+    }
+    if mouse_input.just_released(MouseButton::Middle) {
+        *drag_from = None;
+    }
+    if let Some(from) = *drag_from {
+        let delta = (from - mouse_res.screen) * view.size();
+        view.pan(delta);
+        *drag_from = Some(mouse_res.screen);
+    }
+
+    if keys.just_pressed(Action::FrameAll) {
+        let mut coords: Vec<Vec2> = vec![];
+        for curve in area.document() {
+            let prepared = curve.prepare();
+            coords.extend(curve.points().iter().map(|p| Vec2::new(p.x, p.y)));
+            // overshoot between points
+            coords.extend((0..=100).map(|n| {
+                let x = n as f32 * 0.01;
+                Vec2::new(x, prepared.sample(x).clamp(0.0, 1.0))
+            }));
+        }
+
+        view.frame(&coords);
+    }
+}
+
+/// click on point selects it (with Shift - toggles) and starts moving of selection,
+/// press on empty space starts rubber band selection
+#[allow(clippy::too_many_arguments)]
 fn select_points(
     mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
     mouse_res: Res<MousePlot>,
+    view: Res<View>,
+    settings: Res<Settings>,
     mouse_input: Res<Input<MouseButton>>,
+    keys: Keys,
 ) {
-    // clear selection
     if mouse_input.just_released(MouseButton::Left) {
-        for p in area.points.iter_mut() {
-            if !p.selected {
-                continue;
+        // commit moved points as one history step, points return when x is taken
+        if area.drag.take().is_some() {
+            match area.drag_moves() {
+                Ok(moves) => history.apply(&mut area, Command::MoveGroup(moves)),
+                Err(err) => {
+                    status_bar.show_error(err.as_str());
+                    for p in area.points.iter_mut() {
+                        p.uncommited = p.commited;
+                    }
+                }
             }
+        }
 
-            p.selected = false;
-            p.commited = p.uncommited;
+        if let Some((from, to)) = area.band.take() {
+            area.select_rect(from, to);
         }
         return;
     }
 
+    if let Some((from, _)) = area.band {
+        area.band = Some((from, mouse_res.coords));
+    }
+
     // try select
-    if !mouse_input.just_pressed(MouseButton::Left) {
+    if !mouse_input.just_pressed(MouseButton::Left) || area.has_moving_points() {
         return;
     }
 
-    // find closest in distance
-    let closest = area.closest(mouse_res.coords);
-    let dist = Vec2::new(closest.commited.x, closest.commited.y).distance(mouse_res.coords);
-    if dist > ACTIVE_RADIUS {
+    // tangent handle of focused point is under cursor
+    if area
+        .handle_at(mouse_res.coords, &view, settings.active_radius)
+        .is_some()
+    {
         return;
     }
 
-    // set active
-    for p in area.points.iter_mut() {
-        if p.clone() == closest {
-            p.selected = true;
+    let additive = keys.pressed(Action::AddToSelection);
+
+    // find closest in distance
+    let hovered = area
+        .closest(mouse_res.coords, &view)
+        .filter(|p| view.distance(p.commited, mouse_res.coords) <= settings.active_radius);
+    if let Some(closest) = hovered {
+        if additive {
+            area.toggle_selection(closest.commited);
+            area.focused = Some(closest.commited);
+            return;
+        }
+
+        // click on selected point moves whole selection
+        if !closest.selected {
+            area.select(closest.commited, false);
         }
+        area.focused = Some(closest.commited);
+        area.drag = Some(Drag {
+            from: mouse_res.coords,
+            anchor: closest.commited,
+        });
+        return;
     }
+
+    // new point will be created on curve
+    let ghost = area.interpolate(mouse_res.coords.x);
+    if view.distance(mouse_res.coords, ghost) <= settings.active_radius {
+        return;
+    }
+
+    area.focused = None;
+    if !additive {
+        area.clear_selection();
+    }
+    area.band = Some((mouse_res.coords, mouse_res.coords));
 }
 
-fn move_points(mut area: ResMut<Area>, mouse_res: Res<MousePlot>, keyboard: Res<Input<KeyCode>>) {
+/// selected points follow mouse delta (not absolute position), so group keeps its shape
+fn move_points(
+    mut area: ResMut<Area>,
+    mouse_res: Res<MousePlot>,
+    view: Res<View>,
+    settings: Res<Settings>,
+    snap: Res<Snap>,
+    keys: Keys,
+) {
     #[derive(PartialEq)]
     enum Axis {
         Both,
@@ -304,54 +711,52 @@ fn move_points(mut area: ResMut<Area>, mouse_res: Res<MousePlot>, keyboard: Res<
         OnlyY,
     }
 
+    let drag = match area.drag {
+        Some(drag) => drag,
+        None => return,
+    };
+
     let mut axis = Axis::Both;
 
-    if keyboard.pressed(KeyCode::X) {
+    if keys.pressed(Action::AxisLockX) {
         axis = Axis::OnlyX
-    } else if keyboard.pressed(KeyCode::Y) {
+    } else if keys.pressed(Action::AxisLockY) {
         axis = Axis::OnlyY
     }
 
-    let snap = keyboard.pressed(KeyCode::ControlLeft);
+    let grid = snap.grid != keys.pressed(Action::Snap);
+
+    // snap grabbed point, others keep distance to it
+    let target = snap.apply(
+        drag.anchor + mouse_res.coords - drag.from,
+        grid,
+        &area,
+        &view,
+        &settings,
+    );
+    let mut delta = target - drag.anchor;
+    if axis == Axis::OnlyX {
+        delta.y = 0.0;
+    }
+    if axis == Axis::OnlyY {
+        delta.x = 0.0;
+    }
 
     for p in area.points.iter_mut() {
         if !p.selected {
             continue;
         }
 
-        // reset
-        p.uncommited = p.commited;
-
-        // move
-        if axis != Axis::OnlyY {
-            p.uncommited.x = mouse_res.coords.x;
-        }
-        if axis != Axis::OnlyX {
-            p.uncommited.y = mouse_res.coords.y;
-        }
-
-        // snap
-        if snap {
-            p.uncommited.x = roundf32(p.uncommited.x, 1);
-            p.uncommited.y = roundf32(p.uncommited.y, 1);
-        }
-
-        // clamp
-        p.uncommited.x = p.uncommited.x.clamp(0.01, 0.99);
-
-        // reset start/end
-        if p.commited.x == 0.0 {
-            p.uncommited.x = 0.0;
-        }
-        if p.commited.x == 1.0 {
-            p.uncommited.x = 1.0;
-        }
+        p.uncommited = Area::moved(p, delta);
     }
 }
 
 fn create_points(
     mut area: ResMut<Area>,
+    mut history: ResMut<History>,
     mouse_res: Res<MousePlot>,
+    view: Res<View>,
+    settings: Res<Settings>,
     mouse_input: Res<Input<MouseButton>>,
 ) {
     if !mouse_input.just_pressed(MouseButton::Left) {
@@ -362,27 +767,42 @@ fn create_points(
         return;
     }
 
+    if area
+        .handle_at(mouse_res.coords, &view, settings.active_radius)
+        .is_some()
+    {
+        return;
+    }
+
     // find future point place
     let ghost = area.interpolate(mouse_res.coords.x);
-    let closest = area.closest(mouse_res.coords);
+    let closest = area.closest(mouse_res.coords, &view);
 
     // new point very close to exist
-    if closest.commited.distance(ghost) < (ACTIVE_RADIUS * 1.1) {
+    if closest.is_some_and(|p| view.distance(p.commited, ghost) < (settings.active_radius * 1.1)) {
         return;
     }
 
     // mouse far away of new potential point (just click around plot)
-    if mouse_res.coords.distance(ghost) > ACTIVE_RADIUS {
+    if view.distance(mouse_res.coords, ghost) > settings.active_radius {
         return;
     }
 
-    // add to area
-    area.insert(ghost, true);
+    // add to area, it can be moved until mouse release
+    history.apply(&mut area, Command::Insert(ghost));
+    area.select(ghost, false);
+    area.drag = Some(Drag {
+        from: mouse_res.coords,
+        anchor: ghost,
+    });
 }
 
 fn delete_points(
     mut area: ResMut<Area>,
+    mut history: ResMut<History>,
     mouse_res: Res<MousePlot>,
+    view: Res<View>,
+    settings: Res<Settings>,
     mouse_input: Res<Input<MouseButton>>,
 ) {
     if !mouse_input.just_pressed(MouseButton::Right) {
@@ -393,63 +813,420 @@ fn delete_points(
         return;
     }
 
-    // find point under cursor
-    let closest = area.closest(mouse_res.coords);
-    if closest.commited.distance(closest.commited) >= ACTIVE_RADIUS {
+    // find point under cursor, mouse far away of it is just click around plot
+    let Some(closest) = area
+        .closest(mouse_res.coords, &view)
+        .filter(|p| view.distance(mouse_res.coords, p.commited) <= settings.active_radius)
+    else {
+        return;
+    };
+
+    // click on selected point deletes whole selection
+    let points = match closest.selected {
+        true => area.selected(),
+        false => vec![closest],
+    };
+    history.apply(&mut area, delete_command(&points));
+}
+
+fn delete_selection(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::Delete) {
+        return;
+    }
+
+    if area.has_moving_points() {
         return;
     }
 
-    // mouse far away of deleted point (just click around plot)
-    if mouse_res.coords.distance(closest.commited) > ACTIVE_RADIUS {
+    // without selection focused point is deleted
+    let mut points = area.selected();
+    if points.is_empty() {
+        points.extend(area.focused_point());
+    }
+    if points.is_empty() {
+        status_bar.show_info("nothing selected");
         return;
     }
 
-    area.delete(closest.commited);
+    // keep keyboard editing, focus next point
+    let next = area.neighbor(true);
+    history.apply(&mut area, delete_command(&points));
+    if let Some(next) = next.filter(|n| area.points.iter().any(|p| p.commited == *n)) {
+        area.select(next, false);
+    }
 }
 
-fn set_dirty_state(mut file: ResMut<AttachedFile>, area: Res<Area>) {
-    // check if already dirty
-    if file.dirty {
+/// arrows - focus previous/next point, Shift+arrows - nudge selection, Insert - add point
+/// in the middle of focused segment
+fn keyboard_edit(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    settings: Res<Settings>,
+    keys: Keys,
+) {
+    if area.has_moving_points() {
+        return;
+    }
+
+    for (action, forward) in [(Action::FocusPrev, false), (Action::FocusNext, true)] {
+        if keys.just_pressed(action) {
+            if let Some(p) = area.neighbor(forward) {
+                area.select(p, false);
+            }
+            return;
+        }
+    }
+
+    let direction = [
+        (Action::NudgeLeft, Vec2::NEG_X),
+        (Action::NudgeRight, Vec2::X),
+        (Action::NudgeUp, Vec2::Y),
+        (Action::NudgeDown, Vec2::NEG_Y),
+    ]
+    .iter()
+    .find(|(action, _)| keys.just_pressed(*action))
+    .map(|(_, direction)| *direction);
+
+    if let Some(direction) = direction {
+        let step = match (
+            keys.pressed(Action::NudgeCoarse),
+            keys.pressed(Action::NudgeFine),
+        ) {
+            (true, _) => settings.nudge_step_coarse,
+            (_, true) => settings.nudge_step_fine,
+            _ => settings.nudge_step,
+        };
+
+        let mut points = area.selected();
+        if points.is_empty() {
+            points.extend(area.focused_point());
+        }
+
+        match area.nudged(&points, direction * step) {
+            Ok(moves) => history.apply(&mut area, Command::MoveGroup(moves)),
+            Err(err) => status_bar.show_error(err.as_str()),
+        }
         return;
     }
 
-    if is_dirty(&file.state, &area.points) {
-        file.dirty = true;
+    if !keys.just_pressed(Action::InsertPoint) {
         return;
     }
+
+    let Some(middle) = area.segment_middle() else {
+        status_bar.show_info("no focused point");
+        return;
+    };
+
+    history.apply(&mut area, Command::Insert(middle));
+    area.select(middle, false);
 }
 
-fn is_dirty(prev: &Vec<Point>, next: &Vec<Point>) -> bool {
-    if prev.len() != next.len() {
-        return true;
+/// one history step for all points, start/end points are kept
+fn delete_command(points: &[Point]) -> Command {
+    Command::Group(
+        points
+            .iter()
+            .filter(|p| p.commited.x > 0.0 && p.commited.x < 1.0)
+            .map(|p| Command::Delete {
+                at: p.commited,
+                tangent: p.tangent,
+            })
+            .collect(),
+    )
+}
+
+fn drag_tangents(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mouse_res: Res<MousePlot>,
+    view: Res<View>,
+    settings: Res<Settings>,
+    mouse_input: Res<Input<MouseButton>>,
+) {
+    if mouse_input.just_pressed(MouseButton::Left) {
+        let side = area.handle_at(mouse_res.coords, &view, settings.active_radius);
+        if side.is_none() {
+            return;
+        }
+
+        // dragged handle always break tangent, start from current (auto) slopes
+        let (handle_in, handle_out) = area.handles(&view).unwrap();
+        let p = area.focused_point().unwrap();
+        let slope = |handle: Vec2| (handle.y - p.uncommited.y) / (handle.x - p.uncommited.x);
+        let tangent = Tangent::free(slope(handle_in), slope(handle_out));
+
+        for exist_point in area.points.iter_mut() {
+            if exist_point.commited == p.commited {
+                exist_point.uncommited_tangent = tangent;
+            }
+        }
+
+        area.dragging_handle = side;
+        return;
     }
 
-    for n in 0..prev.len() {
-        let p1 = prev[n];
-        let p2 = next[n];
+    let side = match area.dragging_handle {
+        Some(side) => side,
+        None => return,
+    };
 
-        if p1.commited.x != p2.commited.x {
-            return true;
+    let p = match area.focused_point() {
+        Some(p) => p,
+        None => {
+            area.dragging_handle = None;
+            return;
         }
-        if p1.commited.y != p2.commited.y {
-            return true;
+    };
+
+    // commit
+    if mouse_input.just_released(MouseButton::Left) {
+        area.dragging_handle = None;
+        history.apply(
+            &mut area,
+            Command::SetTangent {
+                at: p.commited,
+                from: p.tangent,
+                to: p.uncommited_tangent,
+            },
+        );
+        return;
+    }
+
+    // handle can`t cross point by X
+    let dx = mouse_res.coords.x - p.uncommited.x;
+    let dx = match side {
+        HandleSide::In => dx.min(-0.001),
+        HandleSide::Out => dx.max(0.001),
+    };
+    let slope = (mouse_res.coords.y - p.uncommited.y) / dx;
+
+    // bezier handle length, can`t reach over neighbor point
+    let (width_in, width_out) = area.segment_widths(p.uncommited);
+    let width = match side {
+        HandleSide::In => width_in,
+        HandleSide::Out => width_out,
+    };
+    let weight = match (area.interpolation, width) {
+        (Interpolation::Bezier, Some(width)) => Some((dx.abs() / width).clamp(0.0, 1.0)),
+        _ => None,
+    };
+
+    for exist_point in area.points.iter_mut() {
+        if exist_point.commited != p.commited {
+            continue;
         }
+
+        let tangent = &mut exist_point.uncommited_tangent;
+        match side {
+            HandleSide::In => {
+                tangent.slope_in = slope;
+                tangent.weight_in = weight.unwrap_or(tangent.weight_in);
+            }
+            HandleSide::Out => {
+                tangent.slope_out = slope;
+                tangent.weight_out = weight.unwrap_or(tangent.weight_out);
+            }
+        }
+    }
+}
+
+fn cycle_tangent_mode(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::TangentMode) {
+        return;
+    }
+
+    if area.has_moving_points() {
+        return;
+    }
+
+    let p = match area.focused_point() {
+        Some(p) => p,
+        None => {
+            status_bar.show_info("click on point to change its tangent mode");
+            return;
+        }
+    };
+
+    let mode = p.tangent.mode.next();
+    let to = match mode {
+        // keep curve shape, when tangent is broken
+        TangentMode::Free => {
+            let curve = area.curve();
+            let ind = curve
+                .points()
+                .iter()
+                .position(|cp| cp.x == p.commited.x && cp.y == p.commited.y)
+                .unwrap();
+            let (slope_in, slope_out) = curve.slopes(ind);
+
+            Tangent::free(slope_in, slope_out)
+        }
+        _ => Tangent {
+            mode,
+            ..Tangent::default()
+        },
+    };
+
+    history.apply(
+        &mut area,
+        Command::SetTangent {
+            at: p.commited,
+            from: p.tangent,
+            to,
+        },
+    );
+    status_bar.show_info(format!("tangent: {}", mode).as_str());
+}
+
+fn undo_redo(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    keys: Keys,
+) {
+    let (undo, redo) = (
+        keys.just_pressed(Action::Undo),
+        keys.just_pressed(Action::Redo),
+    );
+    if !undo && !redo {
+        return;
+    }
+
+    if area.has_moving_points() {
+        return;
+    }
+
+    if redo {
+        if !history.redo(&mut area) {
+            status_bar.show_info("nothing to redo");
+        }
+        return;
+    }
+
+    if !history.undo(&mut area) {
+        status_bar.show_info("nothing to undo");
+    }
+}
+
+fn cycle_interpolation(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::Interpolation) {
+        return;
+    }
+
+    if area.has_moving_points() {
+        return;
+    }
+
+    let from = area.interpolation;
+    let to = from.next();
+
+    history.apply(&mut area, Command::SetInterpolation { from, to });
+    status_bar.show_info(format!("interpolation: {}", to).as_str());
+}
+
+fn cycle_clipboard_format(
+    mut clipboard_format: ResMut<ClipboardFormat>,
+    mut status_bar: ResMut<StatusBar>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::ClipboardFormat) {
+        return;
     }
 
-    return false;
+    clipboard_format.format = clipboard_format.format.next();
+    status_bar.show_info(format!("copy format: {}", clipboard_format.format).as_str());
+}
+
+fn switch_curve(mut area: ResMut<Area>, mut status_bar: ResMut<StatusBar>, keys: Keys) {
+    let (forward, back) = (
+        keys.just_pressed(Action::NextCurve),
+        keys.just_pressed(Action::PrevCurve),
+    );
+    if !forward && !back {
+        return;
+    }
+
+    if area.has_moving_points() {
+        return;
+    }
+
+    let count = area.curves.len();
+    let next = match back {
+        true => (area.active + count - 1) % count,
+        false => (area.active + 1) % count,
+    };
+
+    if !area.switch(next) {
+        let hint = format!(
+            "single curve in document ({} - add curve)",
+            keys.chord(Action::AddCurve)
+        );
+        status_bar.show_info(hint.as_str());
+        return;
+    }
+
+    let info = format!("curve {}/{}: {}", next + 1, count, area.curve_label());
+    status_bar.show_info(info.as_str());
+}
+
+fn add_curve(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::AddCurve) {
+        return;
+    }
+
+    if area.has_moving_points() {
+        return;
+    }
+
+    let curve = Curve::new(vec![
+        spline_curve::Point::new(0.0, 0.5),
+        spline_curve::Point::new(1.0, 0.5),
+    ]);
+    history.apply(&mut area, Command::AddCurve(curve));
+    status_bar.show_info(format!("curve {} added", area.curves.len()).as_str());
+}
+
+fn set_dirty_state(mut file: ResMut<AttachedFile>, history: Res<History>) {
+    file.dirty = !history.is_saved();
 }
 
 fn save_file(
-    mut file: ResMut<AttachedFile>,
+    file: Res<AttachedFile>,
+    mut history: ResMut<History>,
     mut status_bar: ResMut<StatusBar>,
+    mut prompt: ResMut<Prompt>,
     area: Res<Area>,
-    keyboard: Res<Input<KeyCode>>,
+    keys: Keys,
 ) {
-    if !keyboard.pressed(KeyCode::ControlLeft) {
+    if !keys.just_pressed(Action::Save) {
         return;
     }
 
-    if !keyboard.just_pressed(KeyCode::S) {
+    // nowhere to save, ask for path
+    if !file.attached {
+        prompt.open(PromptKind::SavePath, String::from(DEFAULT_FILE_NAME));
         return;
     }
 
@@ -457,21 +1234,367 @@ fn save_file(
         return;
     }
 
+    if !write_file(&file.file_path, &area, &mut status_bar) {
+        return;
+    }
+
+    history.mark_saved();
+}
+
+/// typing in status bar prompt (any kind), enter is handled by prompt owner
+fn prompt_input(
+    mut prompt: ResMut<Prompt>,
+    mut chars: EventReader<ReceivedCharacter>,
+    keyboard: Res<Input<KeyCode>>,
+) {
+    if !prompt.active {
+        chars.clear();
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::Escape) {
+        prompt.close();
+        return;
+    }
+
+    for ev in chars.iter() {
+        if !ev.char.is_control() {
+            prompt.text.push(ev.char);
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Back) {
+        prompt.text.pop();
+    }
+}
+
+fn save_as(
+    mut file: ResMut<AttachedFile>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    mut prompt: ResMut<Prompt>,
+    area: Res<Area>,
+    keys: Keys,
+) {
+    if !prompt.active {
+        if keys.just_pressed(Action::SaveAs) {
+            let path = match file.attached {
+                true => file.file_path.clone(),
+                false => String::from(DEFAULT_FILE_NAME),
+            };
+
+            prompt.open(PromptKind::SavePath, path);
+        }
+        return;
+    }
+
+    if !prompt.is_open(PromptKind::SavePath) || !keys.prompt_key(KeyCode::Return) {
+        return;
+    }
+
+    let path = prompt.text.trim().to_string();
+    if path.is_empty() {
+        status_bar.show_error("file path is empty");
+        return;
+    }
+
+    // prompt stays open, so path can be fixed
+    if !write_file(&path, &area, &mut status_bar) {
+        return;
+    }
+
+    prompt.close();
+    history.mark_saved();
+    file.attached = true;
+    file.file_path = path;
+}
+
+/// real units of active curve, `x_min:x_max:y_min:y_max` typed in status bar (empty is 0..1)
+fn edit_range(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    mut prompt: ResMut<Prompt>,
+    keys: Keys,
+) {
+    if !prompt.active {
+        if keys.just_pressed(Action::EditRange) && !area.has_moving_points() {
+            prompt.open(PromptKind::Range, area.range().to_string());
+        }
+        return;
+    }
+
+    if !prompt.is_open(PromptKind::Range) || !keys.prompt_key(KeyCode::Return) {
+        return;
+    }
+
+    let text = prompt.text.trim();
+    let to = match text.is_empty() {
+        true => None,
+        false => match text.parse::<Range>() {
+            Ok(range) if range == Range::default() => None,
+            Ok(range) => Some(range),
+            // prompt stays open, so range can be fixed
+            Err(err) => {
+                status_bar.show_error(format!("invalid range: {}", err).as_str());
+                return;
+            }
+        },
+    };
+
+    prompt.close();
+
+    let from = area.metadata.range;
+    history.apply(&mut area, Command::SetRange { from, to });
+    status_bar.show_info(format!("range: {}", area.range()).as_str());
+}
+
+/// exact `x:y` (real units) of hovered or focused point, typed in status bar after Enter
+#[allow(clippy::too_many_arguments)]
+fn edit_point(
+    mut area: ResMut<Area>,
+    mut history: ResMut<History>,
+    mut status_bar: ResMut<StatusBar>,
+    mut prompt: ResMut<Prompt>,
+    mouse_res: Res<MousePlot>,
+    view: Res<View>,
+    settings: Res<Settings>,
+    keys: Keys,
+) {
+    if !prompt.active {
+        if !keys.just_pressed(Action::EditPoint) || area.has_moving_points() {
+            return;
+        }
+
+        // hovered point is more important than focused one
+        let hovered = area
+            .closest(mouse_res.coords, &view)
+            .filter(|p| view.distance(p.commited, mouse_res.coords) <= settings.active_radius);
+        if let Some(closest) = hovered {
+            area.focused = Some(closest.commited);
+        }
+
+        let Some(p) = area.focused_point() else {
+            status_bar.show_info("no point under cursor or focused");
+            return;
+        };
+
+        let real = area.real(p.commited);
+        prompt.open(PromptKind::Point, format!("{}:{}", real.x, real.y));
+        return;
+    }
+
+    if !prompt.is_open(PromptKind::Point) || !keys.prompt_key(KeyCode::Return) {
+        return;
+    }
+
+    let Some(p) = area.focused_point() else {
+        prompt.close();
+        return;
+    };
+
+    // prompt stays open on errors, so value can be fixed
+    let to = match parse_pair(&prompt.text) {
+        Ok(real) => area.normalized(real),
+        Err(err) => {
+            status_bar.show_error(err.as_str());
+            return;
+        }
+    };
+
+    if let Err(err) = area.validate_position(p.commited, to) {
+        status_bar.show_error(err.as_str());
+        return;
+    }
+
+    prompt.close();
+
+    // same as released drag of selection
+    history.apply(&mut area, Command::MoveGroup(vec![(p.commited, to)]));
+}
+
+/// `x:y` typed in prompt (also separated by comma or spaces)
+fn parse_pair(text: &str) -> Result<Vec2, String> {
+    let values: Vec<&str> = text
+        .split(|c: char| c == ':' || c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .collect();
+    let parsed = match values.as_slice() {
+        [x, y] => x
+            .parse::<f32>()
+            .and_then(|x| Ok(Vec2::new(x, y.parse::<f32>()?))),
+        _ => return Err(String::from("expected x:y")),
+    };
+
+    parsed.map_err(|err| format!("not a number: {}", err))
+}
+
+/// G - grid snapping, P - snapping to points, L - snapping to linear value of curve
+fn toggle_snap(mut snap: ResMut<Snap>, mut status_bar: ResMut<StatusBar>, keys: Keys) {
+    let (name, enabled) = if keys.just_pressed(Action::GridSnap) {
+        snap.grid = !snap.grid;
+        ("grid", snap.grid)
+    } else if keys.just_pressed(Action::PointSnap) {
+        snap.points = !snap.points;
+        ("points", snap.points)
+    } else if keys.just_pressed(Action::CurveSnap) {
+        snap.curve = !snap.curve;
+        ("curve", snap.curve)
+    } else {
+        return;
+    };
+
+    let state = match enabled {
+        true => "on",
+        false => "off",
+    };
+    status_bar.show_info(format!("snap to {}: {}", name, state).as_str());
+}
+
+/// Shift+G - grid snap step `x:y` in real units (empty - round step of zoom)
+fn edit_snap_step(
+    mut snap: ResMut<Snap>,
+    mut status_bar: ResMut<StatusBar>,
+    mut prompt: ResMut<Prompt>,
+    keys: Keys,
+) {
+    if !prompt.active {
+        if keys.just_pressed(Action::SnapStep) {
+            let text = match snap.step {
+                Some(step) => format!("{}:{}", step.x, step.y),
+                None => String::new(),
+            };
+            prompt.open(PromptKind::SnapStep, text);
+        }
+        return;
+    }
+
+    if !prompt.is_open(PromptKind::SnapStep) || !keys.prompt_key(KeyCode::Return) {
+        return;
+    }
+
+    let text = prompt.text.trim();
+    let step = match text.is_empty() {
+        true => None,
+        // prompt stays open, so step can be fixed
+        false => match parse_pair(text) {
+            Ok(step) if step.x > 0.0 && step.y > 0.0 => Some(step),
+            Ok(_) => {
+                status_bar.show_error("snap step should be positive");
+                return;
+            }
+            Err(err) => {
+                status_bar.show_error(err.as_str());
+                return;
+            }
+        },
+    };
+
+    prompt.close();
+    snap.step = step;
+    match step {
+        Some(step) => status_bar.show_info(format!("snap step: {}:{}", step.x, step.y).as_str()),
+        None => status_bar.show_info("snap step: by zoom"),
+    }
+}
+
+/// lookup table export, next to attached file (raw f32 and text listing)
+fn bake_lut(
+    file: Res<AttachedFile>,
+    mut status_bar: ResMut<StatusBar>,
+    area: Res<Area>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::Bake) {
+        return;
+    }
+
+    if !file.attached {
+        status_bar.show_error("save file first, lut is written next to it");
+        return;
+    }
+
+    let curve = area.curve();
+    let lut = curve.bake(LUT_SIZE);
+    let path = PathBuf::from(&file.file_path);
+
+    for (path, content) in [
+        (path.with_extension("lut"), lut.to_bytes()),
+        (path.with_extension("lut.txt"), lut.to_text().into_bytes()),
+    ] {
+        if let Err(err) = std::fs::write(&path, content) {
+            status_bar.show_error(format!("failed save: {} ({:?})", err, path).as_str());
+            return;
+        }
+    }
+
+    status_bar.show_info(
+        format!(
+            "baked {} values to {:?} (max error {:.5})",
+            lut.len(),
+            path.with_extension("lut"),
+            lut.max_error(&curve)
+        )
+        .as_str(),
+    );
+}
+
+/// png of plot next to attached file, rendered without gpu (same as cli render)
+fn render_preview(
+    file: Res<AttachedFile>,
+    mut status_bar: ResMut<StatusBar>,
+    area: Res<Area>,
+    settings: Res<Settings>,
+    keys: Keys,
+) {
+    if !keys.just_pressed(Action::Render) {
+        return;
+    }
+
     if !file.attached {
+        status_bar.show_error("save file first, png is written next to it");
         return;
     }
 
-    let mut data: Vec<Vec2> = vec![];
-    for p in area.points.clone() {
-        data.push(p.commited);
+    let path = PathBuf::from(&file.file_path).with_extension("png");
+    let canvas = render(
+        &area.document(),
+        area.active,
+        &settings,
+        (PLOT_WIDTH * WINDOW_SCALE) as u32,
+        (PLOT_HEIGHT * WINDOW_SCALE) as u32,
+    );
+
+    let result = canvas
+        .encode_png()
+        .and_then(|png| std::fs::write(&path, png).map_err(|err| err.to_string()));
+
+    match result {
+        Ok(_) => status_bar.show_info(format!("rendered to {:?}", path).as_str()),
+        Err(err) => status_bar.show_error(format!("failed render: {} ({:?})", err, path).as_str()),
     }
+}
 
-    let content = encode(data);
-    match std::fs::write(PathBuf::from(file.file_path.clone()), content) {
-        Err(err) => status_bar.show_error(format!("failed save: {}", err).as_str()),
-        _ => status_bar.show_info(format!("file {} saved!", file.file_path).as_str()),
+/// format by file extension, `.curve` for unknown
+fn write_file(path: &str, area: &Area, status_bar: &mut StatusBar) -> bool {
+    let format = Format::from_path(path).unwrap_or_default();
+    if let Err(err) = format.check_document(&area.document()) {
+        status_bar.show_error(format!("failed save: {}", err).as_str());
+        return false;
     }
 
-    file.dirty = false;
-    file.state = area.points.clone();
+    let content = area.encode(format);
+    match std::fs::write(PathBuf::from(path), content) {
+        Err(err) => {
+            status_bar.show_error(format!("failed save: {}", err).as_str());
+            false
+        }
+        _ => {
+            status_bar.show_info(format!("file {} saved!", path).as_str());
+            true
+        }
+    }
+}
+
+fn prompt_closed(prompt: Res<Prompt>) -> bool {
+    !prompt.active
 }
@@ -0,0 +1,298 @@
+use spline_curve::Curve;
+
+use crate::consts::*;
+use crate::resources::View;
+use crate::settings::Settings;
+
+type Rgba = [u8; 4];
+
+// same colors as draw_plot
+const BACKGROUND: Rgba = [0, 0, 0, 255];
+const OUTLINE: Rgba = [128, 128, 128, 255];
+const POINT: Rgba = [255, 215, 0, 255];
+
+/// half size of point box, in window pixels
+const POINT_SIZE: f32 = 5.0;
+
+/// Software RGBA canvas, not depend on bevy renderer (works without GPU/window)
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: Rgba) -> Self {
+        // usize, u32 overflows for big sizes
+        let size = width as usize * height as usize;
+        let mut pixels = Vec::with_capacity(size * 4);
+        for _ in 0..size {
+            pixels.extend_from_slice(&background);
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn pixel(&mut self, x: i32, y: i32, c: Rgba) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+
+        let ind = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[ind..ind + 4].copy_from_slice(&c);
+    }
+
+    pub fn line(&mut self, from: (f32, f32), to: (f32, f32), c: Rgba) {
+        let steps = (to.0 - from.0)
+            .abs()
+            .max((to.1 - from.1).abs())
+            .ceil()
+            .max(1.0);
+
+        for n in 0..=(steps as i32) {
+            let t = n as f32 / steps;
+            let x = from.0 + (to.0 - from.0) * t;
+            let y = from.1 + (to.1 - from.1) * t;
+
+            self.pixel(x.round() as i32, y.round() as i32, c);
+        }
+    }
+
+    /// outline of rectangle
+    pub fn rect(&mut self, tl: (f32, f32), br: (f32, f32), c: Rgba) {
+        self.line(tl, (br.0, tl.1), c);
+        self.line((br.0, tl.1), br, c);
+        self.line(br, (tl.0, br.1), c);
+        self.line((tl.0, br.1), tl, c);
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        let mut data: Vec<u8> = vec![];
+
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|err| err.to_string())?;
+        writer.finish().map_err(|err| err.to_string())?;
+
+        Ok(data)
+    }
+
+    /// normalized plot coords (0..1, Y up) to canvas pixels (Y down)
+    fn plot(&self, x: f32, y: f32) -> (f32, f32) {
+        let offset = OFFSET * WINDOW_SCALE;
+        let w = self.width as f32 - (offset * 2.0);
+        let h = self.height as f32 - (offset * 2.0);
+
+        (offset + (x * w), offset + ((1.0 - y) * h))
+    }
+
+    /// segment per pixel column, color by x
+    fn spline(&mut self, curve: &Curve, color: impl Fn(f32) -> Rgba) {
+        let steps = self.width.max(2);
+        let prepared = curve.prepare();
+
+        let mut prev = self.plot(0.0, prepared.sample(0.0).clamp(0.0, 1.0));
+        for n in 1..=steps {
+            let x = n as f32 / steps as f32;
+            let cur = self.plot(x, prepared.sample(x).clamp(0.0, 1.0));
+
+            self.line(prev, cur, color(x));
+            prev = cur;
+        }
+    }
+
+    /// canvas pixels in one window pixel (1.0 for default size)
+    fn scale(&self) -> f32 {
+        self.width as f32 / (PLOT_WIDTH * WINDOW_SCALE)
+    }
+}
+
+/// Same plot as editor (`draw_plot`) without zoom: outline, grid of range units
+/// (adapts to range like editor, no labels), other curves of document dimmed,
+/// linear polyline, spline colored by x (own hue with several curves), point boxes
+pub fn render(
+    curves: &[Curve],
+    active: usize,
+    settings: &Settings,
+    width: u32,
+    height: u32,
+) -> Canvas {
+    let mut canvas = Canvas::new(width, height, BACKGROUND);
+    let scale = canvas.scale();
+    let hue = |ind: usize| settings.curve_hues[ind % settings.curve_hues.len()];
+
+    // outline, one plot unit above and below plot
+    let (left, top) = canvas.plot(0.0, 1.0);
+    let (right, bottom) = canvas.plot(1.0, 0.0);
+    canvas.rect(
+        (left, top - scale * WINDOW_SCALE),
+        (right, bottom + scale * WINDOW_SCALE),
+        OUTLINE,
+    );
+
+    // lines on plot edges are only labels in editor
+    let curve = &curves[active];
+    let range = curve.metadata().range.unwrap_or_default();
+    let (lines_x, lines_y) = View::default().grid(range, None, settings.grid_lines);
+    let inside = |at: f32| at > 0.0001 && at < 0.9999;
+
+    for line in lines_y.iter().filter(|line| inside(line.at)) {
+        let (from, to) = (canvas.plot(0.0, line.at), canvas.plot(1.0, line.at));
+        canvas.line(from, to, gray(0.05));
+    }
+    for line in lines_x.iter().filter(|line| inside(line.at)) {
+        let (from, to) = (canvas.plot(line.at, 0.0), canvas.plot(line.at, 1.0));
+        canvas.line(from, to, gray(0.03));
+    }
+
+    let size = POINT_SIZE * scale;
+    for (ind, layer) in curves.iter().enumerate().filter(|(ind, _)| *ind != active) {
+        let color = hsl(hue(ind), 0.75, 0.25);
+        canvas.spline(layer, |_| color);
+
+        for p in layer.points() {
+            let (x, y) = canvas.plot(p.x, p.y);
+            let size = size * 0.6;
+            canvas.rect((x - size, y - size), (x + size, y + size), color);
+        }
+    }
+
+    let points = curve.points();
+    for pair in points.windows(2) {
+        let from = canvas.plot(pair[0].x, pair[0].y);
+        let to = canvas.plot(pair[1].x, pair[1].y);
+        canvas.line(from, to, gray(0.35));
+    }
+
+    match curves.len() > 1 {
+        true => {
+            let color = hsl(hue(active), 0.75, 0.5);
+            canvas.spline(curve, |_| color);
+        }
+        false => canvas.spline(curve, |x| hsl(x * 360.0, 0.75, 0.5)),
+    }
+
+    for p in points {
+        let (x, y) = canvas.plot(p.x, p.y);
+        canvas.rect((x - size, y - size), (x + size, y + size), POINT);
+    }
+
+    canvas
+}
+
+fn gray(lightness: f32) -> Rgba {
+    hsl(0.0, 0.0, lightness)
+}
+
+/// hsl (hue in degrees) to srgb, same as bevy `Color::hsl`
+fn hsl(hue: f32, saturation: f32, lightness: f32) -> Rgba {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let m = lightness - chroma / 2.0;
+    let channel = |v: f32| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+    [channel(r), channel(g), channel(b), 255]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use spline_curve::{Interpolation, Metadata, Point, Range};
+
+    impl Canvas {
+        fn at(&self, x: f32, y: f32) -> Rgba {
+            let ind = ((y.round() as u32 * self.width + x.round() as u32) * 4) as usize;
+            self.pixels[ind..ind + 4].try_into().unwrap()
+        }
+    }
+
+    #[test]
+    pub fn render_test() {
+        let curve = Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)])
+            .with_interpolation(Interpolation::Constant);
+        let canvas = render(
+            std::slice::from_ref(&curve),
+            0,
+            &Settings::default(),
+            960,
+            480,
+        );
+
+        // grid lines of default range (steps 0.2 and 0.25), out of spline and points
+        let (x, y) = canvas.plot(0.3, 0.25);
+        assert_eq!(canvas.at(x, y), gray(0.05));
+        let (x, y) = canvas.plot(0.4, 0.6);
+        assert_eq!(canvas.at(x, y), gray(0.03));
+        let (x, y) = canvas.plot(0.5, 0.6);
+        assert_eq!(canvas.at(x, y), BACKGROUND);
+
+        // linear polyline is under step spline
+        let (x, y) = canvas.plot(0.3, 0.3);
+        assert_eq!(canvas.at(x, y), gray(0.35));
+        // hue of pixel column (neighbor column can overdraw it)
+        let (x, y) = canvas.plot(0.3, 0.0);
+        let (color, expected) = (canvas.at(x, y), hsl(0.3 * 360.0, 0.75, 0.5));
+        for c in 0..4 {
+            assert!(color[c].abs_diff(expected[c]) <= 2, "{:?}", color);
+        }
+
+        // point box corner
+        let (x, y) = canvas.plot(1.0, 1.0);
+        assert_eq!(canvas.at(x - POINT_SIZE, y + POINT_SIZE), POINT);
+
+        let png = canvas.encode_png().unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[test]
+    pub fn render_document_test() {
+        let settings = Settings::default();
+        let flat = Curve::new(vec![Point::new(0.0, 0.8), Point::new(1.0, 0.8)]);
+        let range = "0:10:0:100".parse::<Range>().unwrap();
+        let active =
+            Curve::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)]).with_metadata(Metadata {
+                range: Some(range),
+                ..Default::default()
+            });
+        let canvas = render(&[flat, active], 1, &settings, 960, 480);
+
+        // other curve is dimmed, active curve has own hue
+        let (x, y) = canvas.plot(0.55, 0.8);
+        assert_eq!(canvas.at(x, y), hsl(settings.curve_hues[0], 0.75, 0.25));
+        let (x, y) = canvas.plot(0.55, 0.0);
+        assert_eq!(canvas.at(x, y), hsl(settings.curve_hues[1], 0.75, 0.5));
+
+        // grid of active curve range, x step 2 (0.2), y step 25 (0.25)
+        let (x, y) = canvas.plot(0.2, 0.6);
+        assert_eq!(canvas.at(x, y), gray(0.03));
+        let (x, y) = canvas.plot(0.3, 0.5);
+        assert_eq!(canvas.at(x, y), gray(0.05));
+    }
+
+    #[test]
+    pub fn hsl_test() {
+        assert_eq!(hsl(0.0, 1.0, 0.5), [255, 0, 0, 255]);
+        assert_eq!(hsl(120.0, 1.0, 0.5), [0, 255, 0, 255]);
+        assert_eq!(hsl(360.0, 1.0, 0.5), [255, 0, 0, 255]);
+        assert_eq!(gray(0.5), [128, 128, 128, 255]);
+    }
+}
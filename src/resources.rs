@@ -1,17 +1,56 @@
+use std::path::PathBuf;
+
 use bevy::prelude::*;
+use spline_curve::{Curve, Format, Interpolation, Metadata, Range, Tangent, TangentMode};
+
+use crate::consts::*;
+use crate::settings::Settings;
 
 #[derive(Resource, Default)]
 pub struct AttachedFile {
     pub attached: bool,
     pub file_path: String,
     pub dirty: bool,
-    pub state: Vec<Point>,
+}
+
+/// editor launch arguments (not a cli subcommand)
+#[derive(Resource, Default)]
+pub struct LaunchArgs {
+    pub file: Option<PathBuf>,
+}
+
+/// text input in status bar ("save as" path, range), `kind` is what enter applies
+#[derive(Resource, Default)]
+pub struct Prompt {
+    pub active: bool,
+    pub kind: PromptKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum PromptKind {
+    #[default]
+    SavePath,
+    Range,
+    /// `x:y` of focused point in real units
+    Point,
+    /// `x:y` grid snap step in real units
+    SnapStep,
+}
+
+/// format of copied text (pasted text format is detected)
+#[derive(Resource, Default)]
+pub struct ClipboardFormat {
+    pub format: Format,
 }
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct Point {
     pub commited: Vec2,
     pub uncommited: Vec2,
+    pub tangent: Tangent,
+    pub uncommited_tangent: Tangent,
+    /// part of selection (moved and deleted together)
     pub selected: bool,
 }
 
@@ -20,14 +59,44 @@ impl Point {
         Self {
             commited: Vec2::new(x, y),
             uncommited: Vec2::new(x, y),
+            tangent: Tangent::default(),
+            uncommited_tangent: Tangent::default(),
             selected: false,
         }
     }
 }
 
+/// moving of selected points by mouse delta
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Drag {
+    /// mouse coords at press
+    pub from: Vec2,
+    /// grabbed point (snapping is applied to it, others are moved by same delta)
+    pub anchor: Vec2,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HandleSide {
+    In,
+    Out,
+}
+
 #[derive(Resource)]
 pub struct Area {
     pub points: Vec<Point>,
+    pub interpolation: Interpolation,
+    /// file header data (name, range, unknown keys), kept for save
+    pub metadata: Metadata,
+    /// last clicked/created point, tangent handles drawn for it
+    pub focused: Option<Vec2>,
+    pub dragging_handle: Option<HandleSide>,
+    pub drag: Option<Drag>,
+    /// rubber band selection rectangle (press and current mouse coords)
+    pub band: Option<(Vec2, Vec2)>,
+    /// all curves of document, slot of active curve is stale while it is edited (see `document`)
+    pub curves: Vec<Curve>,
+    /// index of editable curve, other curves are drawn dimmed
+    pub active: usize,
 }
 
 #[derive(Resource, Default)]
@@ -45,12 +114,52 @@ pub struct StatusTimedText {
 
 #[derive(Resource, Default)]
 pub struct MousePlot {
+    /// curve coords under cursor (normalized, clamped to view)
     pub coords: Vec2,
+    /// cursor position on plot area (0..1 inside plot, Y up), not clamped
+    pub screen: Vec2,
+}
+
+/// Window size in logical pixels, updated every frame (resize, fullscreen).
+/// Plot takes everything above status bar
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct Layout {
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Visible part of curve (normalized coords), plot area shows `min..max`.
+/// Always inside 0..1, zoom is separate per axis (so "frame all" fits any points)
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct View {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+/// Snapping of dragged points (grabbed point snaps, others keep distance to it).
+/// Grid snapping is inverted while Ctrl is held
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub struct Snap {
+    pub grid: bool,
+    /// grid step per axis in real units (editor grid shows it too),
+    /// None is round step of current zoom
+    pub step: Option<Vec2>,
+    /// to x or y of points of other curves, to y of not moved points of same curve
+    pub points: bool,
+    /// to linear value of curve (between not moved points)
+    pub curve: bool,
+}
+
+/// grid line position (normalized) with its value in real units
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GridLine {
+    pub at: f32,
+    pub value: f32,
 }
 
 impl Area {
     pub fn new() -> Self {
-        Self {
+        let mut area = Self {
             points: vec![
                 Point::new(0.0, 0.5),
                 Point::new(0.2, 0.3),
@@ -59,71 +168,516 @@ impl Area {
                 Point::new(0.85, 0.05),
                 Point::new(1.0, 0.5),
             ],
-        }
+            interpolation: Interpolation::default(),
+            metadata: Metadata::default(),
+            focused: None,
+            dragging_handle: None,
+            drag: None,
+            band: None,
+            curves: vec![],
+            active: 0,
+        };
+
+        area.curves = vec![area.curve()];
+        area
     }
 
-    pub fn insert(&mut self, p: Vec2, select_created: bool) {
+    pub fn insert(&mut self, p: Vec2, select_created: bool) -> bool {
         if p.x <= 0.0 {
-            return;
+            return false;
         }
         if p.x >= 1.0 {
-            return;
+            return false;
         }
 
         self.points.push(Point {
-            commited: p,
-            uncommited: p,
             selected: select_created,
-        })
+            ..Point::new(p.x, p.y)
+        });
+
+        true
     }
 
-    pub fn delete(&mut self, p: Vec2) {
+    pub fn delete(&mut self, p: Vec2) -> bool {
         if p.x <= 0.0 {
-            return;
+            return false;
         }
         if p.x >= 1.0 {
-            return;
+            return false;
         }
 
         let exist_points = self.points.clone();
         self.points.clear();
 
-        for exist_point in exist_points {
+        for exist_point in exist_points.iter() {
             if exist_point.commited == p {
                 continue;
             }
 
-            self.points.push(exist_point);
+            self.points.push(*exist_point);
+        }
+
+        if self.focused == Some(p) {
+            self.focused = None;
         }
+
+        self.points.len() != exist_points.len()
+    }
+
+    pub fn replace(&mut self, curve: &Curve) {
+        self.points = curve
+            .points()
+            .iter()
+            .map(|p| Point {
+                tangent: p.tangent,
+                uncommited_tangent: p.tangent,
+                ..Point::new(p.x, p.y)
+            })
+            .collect();
+        self.interpolation = curve.interpolation();
+        self.metadata = curve.metadata().clone();
+        self.focused = None;
+        self.drag = None;
+        self.band = None;
+    }
+
+    /// all curves, with current state of active one
+    pub fn document(&self) -> Vec<Curve> {
+        let mut curves = self.curves.clone();
+        curves[self.active] = self.curve();
+        curves
+    }
+
+    /// not editable curves with their indexes
+    pub fn layers(&self) -> impl Iterator<Item = (usize, &Curve)> {
+        let active = self.active;
+        self.curves
+            .iter()
+            .enumerate()
+            .filter(move |(ind, _)| *ind != active)
+    }
+
+    /// make curve editable, returns false for already active or unknown curve
+    pub fn switch(&mut self, ind: usize) -> bool {
+        if ind == self.active || ind >= self.curves.len() {
+            return false;
+        }
+
+        self.curves[self.active] = self.curve();
+        self.active = ind;
+
+        let curve = self.curves[ind].clone();
+        self.replace(&curve);
+        true
+    }
+
+    pub fn replace_document(&mut self, curves: &[Curve], active: usize) {
+        if curves.is_empty() {
+            return;
+        }
+
+        self.curves = curves.to_vec();
+        self.active = active.min(curves.len() - 1);
+
+        let curve = self.curves[self.active].clone();
+        self.replace(&curve);
+    }
+
+    /// new last curve, it becomes active
+    pub fn add_curve(&mut self, curve: &Curve) {
+        self.curves[self.active] = self.curve();
+        self.curves.push(curve.clone());
+        self.active = self.curves.len() - 1;
+        self.replace(curve);
+    }
+
+    /// remove last curve (revert of `add_curve`), previous one becomes active
+    pub fn pop_curve(&mut self) -> bool {
+        if self.curves.len() <= 1 {
+            return false;
+        }
+
+        if self.active == self.curves.len() - 1 {
+            self.switch(self.active - 1);
+        }
+        self.curves.pop();
+        true
+    }
+
+    /// curve name for status bar (position in document, when it has no name)
+    pub fn curve_label(&self) -> String {
+        match self.metadata.name.as_str() {
+            "" => format!("curve {}", self.active + 1),
+            name => name.to_string(),
+        }
+    }
+
+    /// decoded curves should be editable (see `Curve::validate`), at least start and end points
+    pub fn decode(data: &str, format: Format) -> Result<Vec<Curve>, String> {
+        let curves = format
+            .decode_all(data)
+            .map_err(|err| format!("invalid format: {}", err))?;
+
+        for (ind, curve) in curves.iter().enumerate() {
+            if let Err(err) = curve.validate() {
+                return Err(match curves.len() {
+                    1 => format!("invalid curve: {}", err),
+                    _ => format!("invalid curve {}: {}", ind + 1, err),
+                });
+            }
+        }
+
+        Ok(curves)
+    }
+
+    /// whole document for formats with several curves, otherwise only active curve
+    pub fn encode(&self, format: Format) -> String {
+        match format.multiple() {
+            true => format.encode_all(&self.document()),
+            false => format.encode(&self.curve()),
+        }
+    }
+
+    pub fn set_tangent(&mut self, at: Vec2, tangent: Tangent) -> bool {
+        let mut changed = false;
+        for p in self.points.iter_mut() {
+            if p.commited != at || p.tangent == tangent {
+                continue;
+            }
+
+            p.tangent = tangent;
+            p.uncommited_tangent = tangent;
+            changed = true;
+        }
+
+        changed
+    }
+
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) -> bool {
+        if self.interpolation == interpolation {
+            return false;
+        }
+
+        self.interpolation = interpolation;
+        true
+    }
+
+    /// real units of active curve (0..1 when curve has no range)
+    pub fn range(&self) -> Range {
+        self.metadata.range.unwrap_or_default()
     }
 
-    pub fn closest(&self, coord: Vec2) -> Point {
+    pub fn set_range(&mut self, range: Option<Range>) -> bool {
+        if self.metadata.range == range {
+            return false;
+        }
+
+        self.metadata.range = range;
+        true
+    }
+
+    /// normalized point in real units of active curve
+    pub fn real(&self, p: Vec2) -> Vec2 {
+        let (x, y) = self.range().real(p.x, p.y);
+        Vec2::new(x, y)
+    }
+
+    /// real units of active curve to normalized point
+    pub fn normalized(&self, p: Vec2) -> Vec2 {
+        let (x, y) = self.range().normalized(p.x, p.y);
+        Vec2::new(x, y)
+    }
+
+    /// new place of point `at` should be inside domain/range and between neighbor points,
+    /// start/end points can`t be moved by X. Errors are in real units
+    pub fn validate_position(&self, at: Vec2, to: Vec2) -> Result<(), String> {
+        let (min, max) = (self.real(Vec2::ZERO), self.real(Vec2::ONE));
+
+        if (at.x == 0.0 || at.x == 1.0) && to.x != at.x {
+            return Err(format!(
+                "x of start/end point should be {}",
+                self.real(at).x
+            ));
+        }
+        if !(0.0..=1.0).contains(&to.x) {
+            return Err(format!("x out of domain {}..{}", min.x, max.x));
+        }
+        if !(0.0..=1.0).contains(&to.y) {
+            return Err(format!("y out of range {}..{}", min.y, max.y));
+        }
+
+        if let (Some(prev), Some(next)) = self.segment_widths(at) {
+            let (prev_x, next_x) = (at.x - prev, at.x + next);
+            if to.x <= prev_x || to.x >= next_x {
+                return Err(format!(
+                    "x should be between neighbor points {}..{}",
+                    self.real(Vec2::new(prev_x, 0.0)).x,
+                    self.real(Vec2::new(next_x, 0.0)).x
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// select point and focus it, other points stay selected only when `additive`
+    pub fn select(&mut self, p: Vec2, additive: bool) {
+        for exist_point in self.points.iter_mut() {
+            if exist_point.commited == p {
+                exist_point.selected = true;
+            } else if !additive {
+                exist_point.selected = false;
+            }
+        }
+
+        self.focused = Some(p);
+    }
+
+    pub fn toggle_selection(&mut self, p: Vec2) {
+        for exist_point in self.points.iter_mut() {
+            if exist_point.commited == p {
+                exist_point.selected = !exist_point.selected;
+            }
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        for p in self.points.iter_mut() {
+            p.selected = false;
+        }
+    }
+
+    /// add points inside rectangle (any corners) to selection
+    pub fn select_rect(&mut self, a: Vec2, b: Vec2) {
+        let (min, max) = (a.min(b), a.max(b));
+        for p in self.points.iter_mut() {
+            if p.commited.cmpge(min).all() && p.commited.cmple(max).all() {
+                p.selected = true;
+            }
+        }
+    }
+
+    pub fn selected(&self) -> Vec<Point> {
+        self.points.iter().filter(|p| p.selected).copied().collect()
+    }
+
+    /// place of point moved by delta: inside 0..1, start/end points keep their x
+    pub fn moved(p: &Point, delta: Vec2) -> Vec2 {
+        let mut to = p.commited + delta;
+
+        to.x = to.x.clamp(0.01, 0.99);
+        to.y = to.y.clamp(0.0, 1.0);
+
+        if p.commited.x == 0.0 || p.commited.x == 1.0 {
+            to.x = p.commited.x;
+        }
+
+        to
+    }
+
+    /// moves of nudged points, refused when a point would reach or pass its neighbor
+    pub fn nudged(&self, points: &[Point], delta: Vec2) -> Result<Vec<(Vec2, Vec2)>, String> {
+        let moves: Vec<(Vec2, Vec2)> = points
+            .iter()
+            .map(|p| (p.commited, Self::moved(p, delta)))
+            .collect();
+
+        // single point has same checks as typed coords
+        if let [(at, to)] = moves[..] {
+            self.validate_position(at, to)?;
+            return Ok(moves);
+        }
+
+        // group keeps order of all points by x (selected neighbors are moved too)
+        let mut xs: Vec<(f32, f32)> = self
+            .points
+            .iter()
+            .map(|p| {
+                let to = moves.iter().find(|(from, _)| *from == p.commited);
+                (p.commited.x, to.map_or(p.commited.x, |(_, to)| to.x))
+            })
+            .collect();
+        xs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        if xs.windows(2).any(|w| w[1].1 <= w[0].1) {
+            return Err(String::from("x should be between neighbor points"));
+        }
+
+        Ok(moves)
+    }
+
+    /// point before/after focused one by x (cycled), first point when nothing is focused
+    pub fn neighbor(&self, forward: bool) -> Option<Vec2> {
+        let mut xs: Vec<Vec2> = self.points.iter().map(|p| p.commited).collect();
+        xs.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+
+        let ind = match self.focused.and_then(|f| xs.iter().position(|p| *p == f)) {
+            Some(ind) if forward => (ind + 1) % xs.len(),
+            Some(ind) => (ind + xs.len() - 1) % xs.len(),
+            None => 0,
+        };
+
+        xs.get(ind).copied()
+    }
+
+    /// middle of segment after focused point (before it for end point), on curve
+    pub fn segment_middle(&self) -> Option<Vec2> {
+        let p = self.focused_point()?;
+        let (prev, next) = self.segment_widths(p.commited);
+
+        let x = match (prev, next) {
+            (_, Some(next)) => p.commited.x + next * 0.5,
+            (Some(prev), None) => p.commited.x - prev * 0.5,
+            (None, None) => return None,
+        };
+
+        Some(Vec2::new(x, self.curve().sample(x).clamp(0.0, 1.0)))
+    }
+
+    /// move all points at once (one point can take place of other in same group)
+    pub fn relocate_group(&mut self, moves: &[(Vec2, Vec2)]) -> bool {
+        let targets: Vec<(usize, Vec2)> = moves
+            .iter()
+            .filter(|(from, to)| from != to)
+            .filter_map(|(from, to)| {
+                let ind = self.points.iter().position(|p| p.commited == *from)?;
+                Some((ind, *to))
+            })
+            .collect();
+
+        for (ind, to) in targets.iter() {
+            let p = &mut self.points[*ind];
+            if self.focused == Some(p.commited) {
+                self.focused = Some(*to);
+            }
+
+            p.commited = *to;
+            p.uncommited = *to;
+        }
+
+        !targets.is_empty()
+    }
+
+    /// closest point on screen (in plot units of view), None for curve without points
+    pub fn closest(&self, coord: Vec2, view: &View) -> Option<Point> {
         let mut points = self.points.clone();
 
         points.sort_by(|a, b| {
-            let dist1 = coord.distance(a.commited);
-            let dist2 = coord.distance(b.commited);
+            let dist1 = view.distance(coord, a.commited);
+            let dist2 = view.distance(coord, b.commited);
 
             dist1.partial_cmp(&dist2).unwrap()
         });
 
-        *points.first().unwrap()
+        points.first().copied()
     }
 
-    pub fn has_moving_points(&self) -> bool {
-        for p in self.points.clone() {
-            if p.selected {
-                return true;
+    pub fn curve(&self) -> Curve {
+        Curve::new(
+            self.points
+                .iter()
+                .map(|p| spline_curve::Point::from(p.commited).with_tangent(p.tangent))
+                .collect(),
+        )
+        .with_interpolation(self.interpolation)
+        .with_metadata(self.metadata.clone())
+    }
+
+    /// curve with not yet commited changes (moving points, dragged handles)
+    pub fn preview_curve(&self) -> Curve {
+        Curve::new(
+            self.points
+                .iter()
+                .map(|p| spline_curve::Point::from(p.uncommited).with_tangent(p.uncommited_tangent))
+                .collect(),
+        )
+        .with_interpolation(self.interpolation)
+        .with_metadata(self.metadata.clone())
+    }
+
+    pub fn focused_point(&self) -> Option<Point> {
+        let focused = self.focused?;
+        self.points.iter().find(|p| p.commited == focused).copied()
+    }
+
+    /// (in, out) tangent handles positions of focused point.
+    /// Linear and constant points have no handles.
+    /// Bezier handles are real control points (length is weight of segment width),
+    /// others have same length on screen with any zoom
+    pub fn handles(&self, view: &View) -> Option<(Vec2, Vec2)> {
+        let p = self.focused_point()?;
+        match p.uncommited_tangent.mode {
+            TangentMode::Auto | TangentMode::Free => {}
+            _ => return None,
+        }
+
+        let curve = self.preview_curve();
+        let ind = curve.points().iter().position(|cp| {
+            cp.x == p.uncommited.x && cp.y == p.uncommited.y && cp.tangent == p.uncommited_tangent
+        })?;
+
+        let (slope_in, slope_out) = curve.slopes(ind);
+        let (weight_in, weight_out) = p.uncommited_tangent.weights();
+        let (width_in, width_out) = self.segment_widths(p.uncommited);
+
+        let direction =
+            |slope: f32, weight: f32, width: Option<f32>| match (self.interpolation, width) {
+                (Interpolation::Bezier, Some(width)) => Vec2::new(1.0, slope) * weight * width,
+                _ => {
+                    (Vec2::new(1.0, slope) / view.size()).normalize() * HANDLE_LENGTH * view.size()
+                }
+            };
+
+        Some((
+            p.uncommited - direction(slope_in, weight_in, width_in),
+            p.uncommited + direction(slope_out, weight_out, width_out),
+        ))
+    }
+
+    /// X distance to (previous, next) point, None for start/end
+    pub fn segment_widths(&self, at: Vec2) -> (Option<f32>, Option<f32>) {
+        let mut prev: Option<f32> = None;
+        let mut next: Option<f32> = None;
+
+        for p in self.points.iter() {
+            let dx = p.uncommited.x - at.x;
+            if dx < 0.0 && prev.map_or(true, |w| -dx < w) {
+                prev = Some(-dx);
+            }
+            if dx > 0.0 && next.map_or(true, |w| dx < w) {
+                next = Some(dx);
             }
         }
 
-        return false;
+        (prev, next)
+    }
+
+    /// handle of focused point within `radius` (plot units)
+    pub fn handle_at(&self, coord: Vec2, view: &View, radius: f32) -> Option<HandleSide> {
+        let (handle_in, handle_out) = self.handles(view)?;
+
+        if view.distance(coord, handle_out) <= radius {
+            return Some(HandleSide::Out);
+        }
+        if view.distance(coord, handle_in) <= radius {
+            return Some(HandleSide::In);
+        }
+
+        None
+    }
+
+    /// mouse interaction in progress (moving points, handle, selection rectangle)
+    pub fn has_moving_points(&self) -> bool {
+        self.dragging_handle.is_some() || self.drag.is_some() || self.band.is_some()
     }
 
     pub fn interpolate(&self, x: f32) -> Vec2 {
         let mut points = self.points.clone();
         points.sort_by(|a, b| a.commited.x.partial_cmp(&b.commited.x).unwrap());
 
+        if points.is_empty() {
+            return Vec2::new(x, 0.0);
+        }
         if x <= 0.0 {
             return points.first().unwrap().commited;
         }
@@ -145,6 +699,379 @@ impl Area {
         let delta = (x - left.commited.x) / (right.commited.x - left.commited.x);
         left.commited.lerp(right.commited, delta)
     }
+
+    /// coords on `axis` (0 - x, 1 - y) of snapping targets: points of other curves and
+    /// not selected points of active curve (only y, same x is duplicated point)
+    pub fn snap_targets(&self, axis: usize) -> Vec<f32> {
+        let own = self
+            .points
+            .iter()
+            .filter(|p| !p.selected && axis == 1)
+            .map(|p| p.commited[axis]);
+        let layers = self
+            .layers()
+            .flat_map(|(_, curve)| curve.points().iter().map(|p| Vec2::new(p.x, p.y)[axis]));
+
+        own.chain(layers).collect()
+    }
+
+    /// moves of dragged (selected) points, refused when point takes x of other point
+    /// (points can pass each other)
+    pub fn drag_moves(&self) -> Result<Vec<(Vec2, Vec2)>, String> {
+        let mut xs: Vec<f32> = self
+            .points
+            .iter()
+            .map(|p| match p.selected {
+                true => p.uncommited.x,
+                false => p.commited.x,
+            })
+            .collect();
+        xs.sort_by(|a, b| a.total_cmp(b));
+
+        if let Some(pair) = xs.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(format!(
+                "x {} is taken by other point",
+                self.real(Vec2::new(pair[0], 0.0)).x
+            ));
+        }
+
+        Ok(self
+            .selected()
+            .iter()
+            .map(|p| (p.commited, p.uncommited))
+            .collect())
+    }
+
+    /// linear value at x between not selected neighbor points, None without both neighbors
+    pub fn linear_value(&self, x: f32) -> Option<f32> {
+        let others = self
+            .points
+            .iter()
+            .filter(|p| !p.selected)
+            .map(|p| p.commited);
+        let left = others
+            .clone()
+            .filter(|p| p.x <= x)
+            .max_by(|a, b| a.x.partial_cmp(&b.x).unwrap())?;
+        let right = others
+            .filter(|p| p.x > x)
+            .min_by(|a, b| a.x.partial_cmp(&b.x).unwrap())?;
+
+        let delta = (x - left.x) / (right.x - left.x);
+        Some(left.lerp(right, delta).y)
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            width: WINDOW_WIDTH,
+            height: WINDOW_HEIGHT,
+        }
+    }
+}
+
+impl Layout {
+    /// (top-left, bottom-right) of plot area in window pixels (Y down), margin is not included
+    pub fn plot_rect(&self) -> (Vec2, Vec2) {
+        let margin = OFFSET * WINDOW_SCALE;
+        let br = Vec2::new(self.width, self.status_bar_top()) - margin;
+
+        (Vec2::splat(margin), br.max(Vec2::splat(margin + 1.0)))
+    }
+
+    pub fn status_bar_top(&self) -> f32 {
+        self.height - STATUS_BAR_HEIGHT
+    }
+
+    /// window pixels (top-left origin, Y down) to world coords of 2d camera (center origin, Y up)
+    pub fn screen(&self, v: Vec2) -> Vec2 {
+        Vec2::new(v.x - (self.width / 2.0), (self.height / 2.0) - v.y)
+    }
+
+    /// cursor position in window to plot area coords (0..1 inside plot, Y up), not clamped
+    pub fn cursor(&self, position: Vec2) -> Vec2 {
+        let (tl, br) = self.plot_rect();
+        let rel = (position - tl) / (br - tl);
+
+        Vec2::new(rel.x, 1.0 - rel.y)
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            min: Vec2::ZERO,
+            max: Vec2::ONE,
+        }
+    }
+}
+
+impl View {
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// curve coords to plot area coords (0..1 is visible)
+    pub fn project(&self, v: Vec2) -> Vec2 {
+        (v - self.min) / self.size()
+    }
+
+    /// plot area coords to curve coords
+    pub fn unproject(&self, v: Vec2) -> Vec2 {
+        self.min + v * self.size()
+    }
+
+    /// curve coords of cursor, rounded to 1/100 of visible size (0.01 without zoom)
+    pub fn cursor(&self, screen: Vec2) -> Vec2 {
+        let v = self.unproject(screen.clamp(Vec2::ZERO, Vec2::ONE));
+        let decimals = |size: f32| 2 + (-size.log10()).ceil().max(0.0) as u32;
+
+        Vec2::new(
+            roundf32(v.x, decimals(self.size().x)),
+            roundf32(v.y, decimals(self.size().y)),
+        )
+    }
+
+    /// distance in plot units, `active_radius` is compared with it (same size on screen with any zoom)
+    pub fn distance(&self, a: Vec2, b: Vec2) -> f32 {
+        ((a - b) / self.size()).length()
+    }
+
+    pub fn contains(&self, v: Vec2) -> bool {
+        v.cmpge(self.min).all() && v.cmple(self.max).all()
+    }
+
+    /// zoom in (factor > 1) or out around curve coords `at`, it stays at same place of plot
+    pub fn zoom(&mut self, at: Vec2, factor: f32) {
+        let rel = self.project(at);
+        let size = (self.size() / factor).clamp(Vec2::splat(MIN_VIEW_SIZE), Vec2::ONE);
+
+        self.min = at - rel * size;
+        self.max = self.min + size;
+        self.fit_bounds();
+    }
+
+    /// move by curve coords delta
+    pub fn pan(&mut self, delta: Vec2) {
+        self.min += delta;
+        self.max += delta;
+        self.fit_bounds();
+    }
+
+    /// show all coords with margin
+    pub fn frame(&mut self, coords: &[Vec2]) {
+        let (mut min, mut max) = (Vec2::ONE, Vec2::ZERO);
+        for v in coords {
+            min = min.min(*v);
+            max = max.max(*v);
+        }
+        if coords.is_empty() {
+            (min, max) = (Vec2::ZERO, Vec2::ONE);
+        }
+
+        let size =
+            ((max - min) * (1.0 + FRAME_MARGIN * 2.0)).clamp(Vec2::splat(MIN_VIEW_SIZE), Vec2::ONE);
+        let center = (min + max) * 0.5;
+
+        self.min = center - size * 0.5;
+        self.max = center + size * 0.5;
+        self.fit_bounds();
+    }
+
+    /// part of segment inside view (Liang-Barsky), None when segment is not visible
+    pub fn clip(&self, a: Vec2, b: Vec2) -> Option<(Vec2, Vec2)> {
+        let d = b - a;
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+
+        for (p, q) in [
+            (-d.x, a.x - self.min.x),
+            (d.x, self.max.x - a.x),
+            (-d.y, a.y - self.min.y),
+            (d.y, self.max.y - a.y),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+
+        if t0 > t1 {
+            return None;
+        }
+
+        Some((a + d * t0, a + d * t1))
+    }
+
+    /// (x, y) grid step in real units, `fixed` is snap step (None - round step for zoom,
+    /// at most `lines` steps per axis)
+    pub fn grid_steps(&self, range: Range, fixed: Option<Vec2>, lines: (usize, usize)) -> Vec2 {
+        if let Some(step) = fixed {
+            return step.abs();
+        }
+
+        let span = self.size() * Vec2::new(range.x_max - range.x_min, range.y_max - range.y_min);
+        Vec2::new(
+            grid_step(span.x.abs(), lines.0),
+            grid_step(span.y.abs(), lines.1),
+        )
+    }
+
+    /// (x, y) grid lines at multiples of `grid_steps`, with too dense fixed step
+    /// only every n-th line is shown (at most `MAX_GRID_LINES` steps per axis).
+    /// Lines on view edges are included (for labels)
+    pub fn grid(
+        &self,
+        range: Range,
+        fixed: Option<Vec2>,
+        lines: (usize, usize),
+    ) -> (Vec<GridLine>, Vec<GridLine>) {
+        let steps = self.grid_steps(range, fixed, lines);
+        let axis = |min: f32, max: f32, real_min: f32, real_max: f32, step: f32| {
+            let real = |v: f32| real_min + (real_max - real_min) * v;
+            let (from, to) = (real(min).min(real(max)), real(min).max(real(max)));
+            let every = ((to - from) / step / MAX_GRID_LINES as f32 - 0.001).ceil();
+            let step = step * every.max(1.0);
+
+            // epsilon keeps lines exactly on edges
+            let eps = step * 0.001;
+            let first = ((from - eps) / step).ceil() as i32;
+            let last = ((to + eps) / step).floor() as i32;
+
+            (first..=last)
+                .map(|n| {
+                    let value = n as f32 * step;
+                    GridLine {
+                        at: (value - real_min) / (real_max - real_min),
+                        value,
+                    }
+                })
+                .collect::<Vec<GridLine>>()
+        };
+
+        (
+            axis(self.min.x, self.max.x, range.x_min, range.x_max, steps.x),
+            axis(self.min.y, self.max.y, range.y_min, range.y_max, steps.y),
+        )
+    }
+
+    /// keep view inside 0..1
+    fn fit_bounds(&mut self) {
+        let size = self.size().min(Vec2::ONE);
+        self.min = self.min.clamp(Vec2::ZERO, Vec2::ONE - size);
+        self.max = self.min + size;
+    }
+}
+
+/// round step (1, 2, 2.5, 5 of power of 10) with at most `lines` steps in span
+fn grid_step(span: f32, lines: usize) -> f32 {
+    let raw = span / lines as f32;
+    let magnitude = 10f32.powf(raw.log10().floor());
+
+    for m in [1.0, 2.0, 2.5, 5.0] {
+        if magnitude * m >= raw * 0.999 {
+            return magnitude * m;
+        }
+    }
+
+    magnitude * 10.0
+}
+
+impl Snap {
+    /// snapped place of grabbed point, `grid` is grid snapping with Ctrl state applied.
+    /// Points and curve snap only inside `active_radius` of view, they win over grid
+    pub fn apply(
+        &self,
+        v: Vec2,
+        grid: bool,
+        area: &Area,
+        view: &View,
+        settings: &Settings,
+    ) -> Vec2 {
+        let mut to = v;
+        let reach = view.size() * settings.active_radius;
+
+        if grid {
+            let step = view.grid_steps(area.range(), self.step, settings.grid_lines);
+            to = area.normalized((area.real(v) / step).round() * step);
+        }
+
+        if self.points {
+            for axis in 0..2 {
+                let nearest = area
+                    .snap_targets(axis)
+                    .into_iter()
+                    .filter(|c| (c - v[axis]).abs() <= reach[axis])
+                    .min_by(|a, b| {
+                        (a - v[axis])
+                            .abs()
+                            .partial_cmp(&(b - v[axis]).abs())
+                            .unwrap()
+                    });
+                if let Some(c) = nearest {
+                    to[axis] = c;
+                }
+            }
+        }
+
+        if self.curve {
+            if let Some(y) = area.linear_value(to.x) {
+                if (y - v.y).abs() <= reach.y {
+                    to.y = y;
+                }
+            }
+        }
+
+        to
+    }
+
+    /// status bar indicator, None when nothing snaps
+    pub fn label(&self, grid: bool) -> Option<String> {
+        let mut parts = vec![];
+        if grid {
+            parts.push(match self.step {
+                Some(step) => format!("grid {}:{}", step.x, step.y),
+                None => String::from("grid"),
+            });
+        }
+        if self.points {
+            parts.push(String::from("points"));
+        }
+        if self.curve {
+            parts.push(String::from("curve"));
+        }
+
+        match parts.is_empty() {
+            true => None,
+            false => Some(format!("[snap: {}]", parts.join(", "))),
+        }
+    }
+}
+
+impl Prompt {
+    pub fn open(&mut self, kind: PromptKind, text: String) {
+        self.active = true;
+        self.kind = kind;
+        self.text = text;
+    }
+
+    pub fn is_open(&self, kind: PromptKind) -> bool {
+        self.active && self.kind == kind
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+        self.text.clear();
+    }
 }
 
 impl StatusBar {
@@ -190,3 +1117,293 @@ impl StatusBar {
         format!("{} ({:.1}s)", text.text, text.ttl).to_string()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn selection_test() {
+        let mut area = Area::new();
+        let xs = |area: &Area| {
+            area.selected()
+                .iter()
+                .map(|p| p.commited.x)
+                .collect::<Vec<f32>>()
+        };
+
+        area.select_rect(Vec2::new(0.5, 1.0), Vec2::new(0.1, 0.0));
+        assert_eq!(xs(&area), vec![0.2, 0.4]);
+
+        area.toggle_selection(Vec2::new(0.2, 0.3));
+        area.toggle_selection(Vec2::new(0.8, 0.9));
+        assert_eq!(xs(&area), vec![0.4, 0.8]);
+
+        area.select(Vec2::new(1.0, 0.5), false);
+        assert_eq!(xs(&area), vec![1.0]);
+        assert_eq!(area.focused, Some(Vec2::new(1.0, 0.5)));
+
+        area.clear_selection();
+        assert!(area.selected().is_empty());
+    }
+
+    #[test]
+    pub fn keyboard_edit_test() {
+        let mut area = Area::new();
+
+        // cycle points by x
+        assert_eq!(area.neighbor(true), Some(Vec2::new(0.0, 0.5)));
+        area.select(Vec2::new(1.0, 0.5), false);
+        assert_eq!(area.neighbor(true), Some(Vec2::new(0.0, 0.5)));
+        assert_eq!(area.neighbor(false), Some(Vec2::new(0.85, 0.05)));
+
+        // end point has only segment before it
+        assert_eq!(area.segment_middle().unwrap().x, 0.925);
+        area.select(Vec2::new(0.2, 0.3), false);
+        let middle = area.segment_middle().unwrap();
+        assert!((middle.x - 0.3).abs() < 0.0001);
+        assert!((middle.y - area.curve().sample(middle.x)).abs() < 0.0001);
+
+        // nudge keeps start/end x and range
+        let start = area.points[0];
+        assert_eq!(
+            Area::moved(&start, Vec2::new(0.1, 0.6)),
+            Vec2::new(0.0, 1.0)
+        );
+
+        // nudge stops at neighbors, (0.2, 0.3) can`t reach x of (0.4, 0.5)
+        let p = area.points[1];
+        assert_eq!(p.commited, Vec2::new(0.2, 0.3));
+        let moves = area.nudged(&[p], Vec2::new(0.1, 0.1)).unwrap();
+        assert!((moves[0].1 - Vec2::new(0.3, 0.4)).length() < 0.0001);
+        let err = area.nudged(&[p], Vec2::new(0.2, 0.0)).unwrap_err();
+        assert!(err.starts_with("x should be between neighbor points"));
+
+        // selected neighbors are moved together, but not past others
+        let group = [area.points[1], area.points[2]];
+        assert_eq!(area.nudged(&group, Vec2::new(0.1, 0.0)).unwrap().len(), 2);
+        assert!(area.nudged(&group, Vec2::new(0.4, 0.0)).is_err());
+    }
+
+    #[test]
+    pub fn validate_position_test() {
+        let mut area = Area::new();
+        area.set_range(Some("0:30:-50:200".parse::<Range>().unwrap()));
+
+        let at = Vec2::new(0.4, 0.5);
+        assert_eq!(area.real(at), Vec2::new(12.0, 75.0));
+        assert!(area
+            .validate_position(at, area.normalized(Vec2::new(15.0, 200.0)))
+            .is_ok());
+        assert_eq!(
+            area.validate_position(at, area.normalized(Vec2::new(25.0, 0.0))),
+            Err(String::from("x should be between neighbor points 6..24"))
+        );
+        assert_eq!(
+            area.validate_position(at, area.normalized(Vec2::new(15.0, 250.0))),
+            Err(String::from("y out of range -50..200"))
+        );
+        assert_eq!(
+            area.validate_position(Vec2::new(1.0, 0.5), Vec2::new(0.9, 0.5)),
+            Err(String::from("x of start/end point should be 30"))
+        );
+        assert!(area
+            .validate_position(Vec2::new(1.0, 0.5), Vec2::new(1.0, 0.1))
+            .is_ok());
+    }
+
+    #[test]
+    pub fn layout_test() {
+        // initial window, same geometry as fixed window
+        let layout = Layout::default();
+        assert_eq!(
+            layout.plot_rect(),
+            (Vec2::splat(20.0), Vec2::new(940.0, 460.0))
+        );
+        assert_eq!(layout.cursor(Vec2::new(20.0, 460.0)), Vec2::ZERO);
+        assert_eq!(layout.screen(Vec2::new(480.0, 256.0)), Vec2::ZERO);
+
+        // plot uses all space above status bar
+        let layout = Layout {
+            width: 1920.0,
+            height: 1080.0,
+        };
+        assert_eq!(layout.plot_rect().1, Vec2::new(1900.0, 1028.0));
+        assert_eq!(layout.cursor(Vec2::new(1900.0, 20.0)), Vec2::ONE);
+    }
+
+    #[test]
+    pub fn view_zoom_test() {
+        let mut view = View::default();
+        assert_eq!(view.cursor(Vec2::new(0.123, 0.5)), Vec2::new(0.12, 0.5));
+
+        // point under cursor stays at same place
+        let at = Vec2::new(0.1, 0.5);
+        let before = view.project(at);
+        view.zoom(at, 4.0);
+        assert!(view.project(at).distance(before) < 0.0001);
+        assert!(view.size().distance(Vec2::splat(0.25)) < 0.0001);
+        // finer cursor with zoom
+        assert_eq!(view.cursor(Vec2::new(0.0123, 0.5)).x, 0.078);
+
+        // never out of 0..1
+        view.zoom(at, 0.01);
+        assert_eq!(view, View::default());
+        view.zoom(at, 4.0);
+        view.pan(Vec2::new(-1.0, 0.0));
+        assert_eq!(view.min.x, 0.0);
+
+        // same size on screen with any zoom
+        assert!((view.distance(Vec2::ZERO, Vec2::new(0.025, 0.0)) - 0.1).abs() < 0.0001);
+    }
+
+    #[test]
+    pub fn view_frame_clip_test() {
+        let mut view = View::default();
+        view.frame(&[Vec2::new(0.0, 0.4), Vec2::new(0.2, 0.6)]);
+        assert_eq!(view.min.x, 0.0);
+        assert!((view.min.y - 0.39).abs() < 0.0001 && (view.max.y - 0.61).abs() < 0.0001);
+
+        let clipped = view.clip(Vec2::new(0.1, 0.0), Vec2::new(0.1, 1.0)).unwrap();
+        assert!((clipped.0.y - view.min.y).abs() < 0.0001);
+        assert!((clipped.1.y - view.max.y).abs() < 0.0001);
+        assert_eq!(view.clip(Vec2::new(0.5, 0.0), Vec2::new(1.0, 1.0)), None);
+    }
+
+    #[test]
+    pub fn grid_test() {
+        let values = |lines: &[GridLine]| lines.iter().map(|l| l.value).collect::<Vec<f32>>();
+
+        let (x, y) = View::default().grid(Range::default(), None, GRID_LINES);
+        assert_eq!(values(&x).len(), 6);
+        assert_eq!(values(&y), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+
+        // round values of real units
+        let range = "0:30:-50:200".parse::<Range>().unwrap();
+        let (x, y) = View::default().grid(range, None, GRID_LINES);
+        assert_eq!(values(&x), vec![0.0, 10.0, 20.0, 30.0]);
+        assert_eq!(values(&y), vec![0.0, 100.0, 200.0]);
+        assert!((y[0].at - 0.2).abs() < 0.0001);
+
+        assert_eq!(grid_step(0.1, 5), 0.02);
+        assert_eq!(grid_step(30.0, 5), 10.0);
+
+        // fixed snap step, too dense one shows every n-th line
+        let (x, y) = View::default().grid(range, Some(Vec2::new(7.5, 50.0)), GRID_LINES);
+        assert_eq!(values(&x), vec![0.0, 7.5, 15.0, 22.5, 30.0]);
+        assert_eq!(values(&y).len(), 6);
+        let (x, _) = View::default().grid(Range::default(), Some(Vec2::splat(0.01)), GRID_LINES);
+        assert_eq!(values(&x).len(), 11);
+    }
+
+    #[test]
+    pub fn snap_test() {
+        let mut area = Area::new();
+        let view = View::default();
+        let mut snap = Snap::default();
+        let settings = Settings::default();
+
+        // nothing enabled
+        let v = Vec2::new(0.33, 0.46);
+        assert_eq!(snap.apply(v, false, &area, &view, &settings), v);
+        assert_eq!(snap.label(false), None);
+
+        // grid step of real units
+        area.set_range(Some("0:30:-50:200".parse::<Range>().unwrap()));
+        snap.step = Some(Vec2::new(3.0, 25.0));
+        let to = area.real(snap.apply(v, true, &area, &view, &settings));
+        assert!((to - Vec2::new(9.0, 75.0)).length() < 0.001);
+        assert_eq!(snap.label(true).unwrap(), "[snap: grid 3:25]");
+
+        // y of near point (0.2, 0.3), its x would be duplicated in same curve
+        area.set_range(None);
+        snap.points = true;
+        let to = snap.apply(Vec2::new(0.21, 0.32), false, &area, &view, &settings);
+        assert_eq!(to, Vec2::new(0.21, 0.3));
+
+        // selected points are moved, so they are not targets
+        area.select(Vec2::new(0.2, 0.3), false);
+        let to = snap.apply(Vec2::new(0.21, 0.32), false, &area, &view, &settings);
+        assert_eq!(to, Vec2::new(0.21, 0.32));
+
+        // x of other curve point
+        area.clear_selection();
+        area.add_curve(&Curve::new(vec![spline_curve::Point::new(0.45, 0.1)]));
+        area.switch(0);
+        let to = snap.apply(Vec2::new(0.46, 0.62), false, &area, &view, &settings);
+        assert_eq!(to, Vec2::new(0.45, 0.62));
+        area.select(Vec2::new(0.4, 0.5), false);
+
+        // linear value between (0.2, 0.3) and (0.8, 0.9)
+        snap.points = false;
+        snap.curve = true;
+        let to = snap.apply(Vec2::new(0.5, 0.61), false, &area, &view, &settings);
+        assert!((to.y - 0.6).abs() < 0.0001);
+        assert_eq!(snap.label(false).unwrap(), "[snap: curve]");
+    }
+
+    #[test]
+    pub fn encode_test() {
+        let mut area = Area::new();
+        assert!(Format::Unity.check_document(&area.document()).is_ok());
+
+        area.add_curve(&Curve::default());
+        let decoded = Format::Json.decode_all(&area.encode(Format::Json)).unwrap();
+        assert_eq!(decoded, area.document());
+
+        // single curve formats have only active curve, save is refused
+        assert_eq!(
+            area.encode(Format::Unity),
+            Format::Unity.encode(&area.curve())
+        );
+        let err = Format::Unity.check_document(&area.document()).unwrap_err();
+        assert!(err.contains("2 curves"));
+    }
+
+    #[test]
+    pub fn decode_test() {
+        let curves = Area::decode("0:0.5\n1:0.5\n", Format::Curve).unwrap();
+        assert_eq!(curves[0].points().len(), 2);
+
+        // empty input is decoded as curve without points, it can`t be edited
+        assert_eq!(
+            Area::decode("", Format::Curve).unwrap_err(),
+            "invalid curve: curve has no points"
+        );
+        assert!(Area::decode("{\"points\":[]}", Format::Json).is_err());
+        let err = Area::decode("#curve v3\n0:0\n1:1\n#curve v3\n", Format::Curve).unwrap_err();
+        assert!(err.starts_with("invalid curve 2:"), "{}", err);
+        assert!(Area::decode("0:0\n0.5:1\n", Format::Curve).is_err());
+        assert!(Area::decode("[1, 2", Format::Json)
+            .unwrap_err()
+            .starts_with("invalid format"));
+
+        // no points, nothing to pick
+        let mut area = Area::new();
+        area.points.clear();
+        assert!(area
+            .closest(Vec2::new(0.5, 0.5), &View::default())
+            .is_none());
+        assert_eq!(area.interpolate(0.5), Vec2::new(0.5, 0.0));
+    }
+
+    #[test]
+    pub fn drag_moves_test() {
+        let mut area = Area::new();
+        area.select(Vec2::new(0.4, 0.5), false);
+
+        // dragged point can pass neighbor
+        area.points[2].uncommited = Vec2::new(0.6, 0.5);
+        assert_eq!(
+            area.drag_moves().unwrap(),
+            vec![(Vec2::new(0.4, 0.5), Vec2::new(0.6, 0.5))]
+        );
+
+        // but not take its x
+        area.points[2].uncommited = Vec2::new(0.2, 0.7);
+        assert_eq!(
+            area.drag_moves().unwrap_err(),
+            "x 0.2 is taken by other point"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::consts::*;
+use crate::keymap::{Action, Keymap};
+
+/// Editor preferences from `settings.ron` in user config directory.
+/// Missing fields are defaults (constants of `consts.rs`), unknown fields are errors
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// window pixels per plot pixel, applied only to initial window size (next start).
+    /// Plot margins and rendered png keep `WINDOW_SCALE`, window can be resized anyway
+    pub window_scale: f32,
+    /// hover/grab distance of points and handles, in plot units
+    pub active_radius: f32,
+    /// max (x, y) grid steps in view, when grid adapts to zoom
+    pub grid_lines: (usize, usize),
+    /// initial grid snap step (x, y) in real units, None - adapts to zoom
+    pub snap_step: Option<(f32, f32)>,
+    /// Shift+arrows nudge (normalized), with Ctrl - coarse, with Alt - fine
+    pub nudge_step: f32,
+    pub nudge_step_coarse: f32,
+    pub nudge_step_fine: f32,
+    /// mouse wheel zoom per line
+    pub zoom_step: f32,
+    /// decimals of moving point coords in status bar, cursor coords have one less
+    pub precision: usize,
+    /// hue of curve by its index in document (repeated for more curves)
+    pub curve_hues: Vec<f32>,
+    pub point_color: Color,
+    pub focused_color: Color,
+    pub selected_color: Color,
+    pub handle_color: Color,
+    /// key chords of actions (`Copy: "Super+C"`), other actions keep default keys
+    pub keys: BTreeMap<Action, String>,
+}
+
+/// Where settings are read from, and modification time of last read (for hot reload)
+#[derive(Resource, Default)]
+pub struct SettingsFile {
+    pub path: Option<PathBuf>,
+    pub modified: Option<SystemTime>,
+    /// seconds since last check of modification time
+    pub elapsed: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window_scale: WINDOW_SCALE,
+            active_radius: ACTIVE_RADIUS,
+            grid_lines: GRID_LINES,
+            snap_step: None,
+            nudge_step: NUDGE_STEP,
+            nudge_step_coarse: NUDGE_STEP_COARSE,
+            nudge_step_fine: NUDGE_STEP_FINE,
+            zoom_step: ZOOM_STEP,
+            precision: COORDS_PRECISION,
+            curve_hues: CURVE_HUES.to_vec(),
+            point_color: Color::GOLD,
+            focused_color: Color::ORANGE,
+            selected_color: Color::WHITE,
+            handle_color: Color::ORANGE,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let settings: Settings = ron::from_str(text).map_err(|err| err.to_string())?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = [
+            ("nudge_step", self.nudge_step),
+            ("nudge_step_coarse", self.nudge_step_coarse),
+            ("nudge_step_fine", self.nudge_step_fine),
+        ];
+        // contains is false for NaN
+        for (name, value) in positive {
+            if !(value > 0.0 && value <= 1.0) {
+                return Err(format!("{} should be in 0..1, got {}", name, value));
+            }
+        }
+
+        if !(0.5..=8.0).contains(&self.window_scale) {
+            return Err(format!(
+                "window_scale should be in 0.5..8, got {}",
+                self.window_scale
+            ));
+        }
+        if !(self.active_radius > 0.0 && self.active_radius <= 0.5) {
+            return Err(format!(
+                "active_radius should be in 0..0.5, got {}",
+                self.active_radius
+            ));
+        }
+        // grid lines are labeled, labels pool is limited
+        let lines = 1..=MAX_GRID_LINES;
+        if !lines.contains(&self.grid_lines.0) || !lines.contains(&self.grid_lines.1) {
+            return Err(format!(
+                "grid_lines should be in 1..{}, got {:?}",
+                MAX_GRID_LINES, self.grid_lines
+            ));
+        }
+        if let Some((x, y)) = self.snap_step {
+            if !(x > 0.0 && y > 0.0 && x.is_finite() && y.is_finite()) {
+                return Err(format!("snap_step should be positive, got {}:{}", x, y));
+            }
+        }
+        if !(self.zoom_step > 1.0 && self.zoom_step.is_finite()) {
+            return Err(format!(
+                "zoom_step should be more than 1, got {}",
+                self.zoom_step
+            ));
+        }
+        if !(1..=6).contains(&self.precision) {
+            return Err(format!(
+                "precision should be in 1..6, got {}",
+                self.precision
+            ));
+        }
+        if self.curve_hues.is_empty() {
+            return Err(String::from("curve_hues should not be empty"));
+        }
+        if let Some(hue) = self.curve_hues.iter().find(|hue| !hue.is_finite()) {
+            return Err(format!("curve_hues should be finite, got {}", hue));
+        }
+        self.keymap()?;
+
+        Ok(())
+    }
+
+    pub fn keymap(&self) -> Result<Keymap, String> {
+        Keymap::new(&self.keys)
+    }
+
+    pub fn snap_step(&self) -> Option<Vec2> {
+        self.snap_step.map(|(x, y)| Vec2::new(x, y))
+    }
+
+    /// initial window size, status bar keeps its height
+    pub fn window_size(&self) -> (f32, f32) {
+        (
+            PLOT_WIDTH * self.window_scale,
+            PLOT_HEIGHT * self.window_scale + STATUS_BAR_HEIGHT,
+        )
+    }
+}
+
+impl SettingsFile {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path, ..default() }
+    }
+
+    /// modification time of file, None when there is no file
+    pub fn modified(&self) -> Option<SystemTime> {
+        let path = self.path.as_ref()?;
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
+/// `settings.ron` in platform config directory
+pub fn settings_path() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+
+    let dir = if cfg!(target_os = "windows") {
+        PathBuf::from(var("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(var("HOME")?).join("Library/Application Support")
+    } else {
+        match var("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(var("HOME")?).join(".config"),
+        }
+    };
+
+    Some(dir.join("rs-spline-editor").join(SETTINGS_FILE_NAME))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn parse_test() {
+        assert_eq!(Settings::parse("()").unwrap(), Settings::default());
+
+        // missing fields are defaults
+        let settings =
+            Settings::parse("(active_radius: 0.05, snap_step: Some((0.5, 10)))").unwrap();
+        assert_eq!(settings.active_radius, 0.05);
+        assert_eq!(settings.snap_step(), Some(Vec2::new(0.5, 10.0)));
+        assert_eq!(settings.zoom_step, ZOOM_STEP);
+
+        let settings = Settings::parse("(point_color: Rgba(red: 1, green: 0, blue: 0, alpha: 1))");
+        assert_eq!(settings.unwrap().point_color, Color::RED);
+
+        assert!(Settings::parse("(unknown: 1)").is_err());
+        assert!(Settings::parse("(zoom_step: \"fast\")").is_err());
+
+        // validation
+        let err = Settings::parse("(active_radius: -1)").unwrap_err();
+        assert!(err.contains("active_radius"));
+        assert!(Settings::parse("(grid_lines: (0, 4))").is_err());
+        assert!(Settings::parse("(curve_hues: [])").is_err());
+        assert!(Settings::parse("(snap_step: Some((0, 1)))").is_err());
+
+        // NaN fails every comparison, it is not in range
+        for field in [
+            "nudge_step",
+            "nudge_step_fine",
+            "window_scale",
+            "active_radius",
+            "zoom_step",
+        ] {
+            let err = Settings::parse(&format!("({}: NaN)", field)).unwrap_err();
+            assert!(err.starts_with(field), "{}", err);
+        }
+        assert!(Settings::parse("(zoom_step: inf)").is_err());
+        assert!(Settings::parse("(snap_step: Some((NaN, 1)))").is_err());
+        assert!(Settings::parse("(curve_hues: [45, NaN])").is_err());
+
+        // keys of actions
+        let settings = Settings::parse("(keys: {Copy: \"Super+C\"})").unwrap();
+        assert_eq!(
+            settings.keymap().unwrap().chord(Action::Copy).to_string(),
+            "Super+C"
+        );
+        assert!(Settings::parse("(keys: {Copy: \"Ctrl+V\"})").is_err());
+        assert!(Settings::parse("(keys: {Launch: \"Ctrl+L\"})").is_err());
+    }
+
+    #[test]
+    pub fn window_size_test() {
+        let (width, height) = Settings::default().window_size();
+        assert_eq!((width, height), (WINDOW_WIDTH, WINDOW_HEIGHT));
+    }
+}